@@ -0,0 +1,111 @@
+//! Parse throughput benchmark over a generated multi-year timesheet archive.
+//!
+//! Run with `cargo bench`. The corpus is generated once per run into the
+//! system temp directory and the release binary is timed against it, so the
+//! numbers include directory scanning, file reads and parsing.
+
+use chrono::{Datelike, NaiveDate};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+const YEARS: i32 = 10;
+const ITERATIONS: usize = 10;
+
+/// Small deterministic generator so every run benchmarks the same corpus
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: u32) -> u32 {
+        self.0 = self.0.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+        u32::try_from((self.0 >> 33) % u64::from(bound)).unwrap_or(0)
+    }
+}
+
+fn day_content(rng: &mut Lcg, date: NaiveDate) -> String {
+    let mut content = String::from("# Daily Notes\n\n");
+
+    if date.weekday().number_from_monday() > 5 {
+        content.push_str("Weekend, nothing logged.\n");
+        return content;
+    }
+
+    if rng.next(40) == 0 {
+        content.push_str("PTO\n");
+        return content;
+    }
+
+    let morning_start = 7 + rng.next(3);
+    let morning_stop = 11 + rng.next(2);
+    let afternoon_start = morning_stop + 1;
+    let afternoon_stop = 16 + rng.next(3);
+    let _ = writeln!(content, "Start work {morning_start}:{:02}", rng.next(60));
+    content.push_str("Worked through the backlog and reviewed pull requests.\n\n");
+    let _ = writeln!(content, "Stop work {morning_stop}:{:02}\n", rng.next(60));
+    content.push_str("Lunch break\n\n");
+    let _ = writeln!(content, "Started working at {afternoon_start}:{:02}", rng.next(60));
+    content.push_str("Afternoon session focused on testing.\n\n");
+    let _ = writeln!(content, "Stopped working at {afternoon_stop}:{:02}", rng.next(60));
+
+    if rng.next(4) == 0 {
+        let _ = writeln!(content, "Work time {} minutes documentation", 15 + rng.next(90));
+    }
+
+    content
+}
+
+fn generate_corpus(dir: &Path) -> usize {
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("failed to create corpus directory");
+
+    let mut rng = Lcg(0x5eed);
+    let start = NaiveDate::from_ymd_opt(2015, 1, 1).expect("valid corpus start date");
+    let end = start.with_year(start.year() + YEARS).expect("valid corpus end date");
+    let mut file_count = 0;
+    for date in start.iter_days().take_while(|date| *date < end) {
+        let path = dir.join(format!("{date}.md"));
+        fs::write(path, day_content(&mut rng, date)).expect("failed to write corpus file");
+        file_count += 1;
+    }
+
+    file_count
+}
+
+fn time_run(binary: &str, dir: &Path) -> Duration {
+    let started = Instant::now();
+    let status = Command::new(binary)
+        .arg(dir)
+        .arg("--summarize")
+        .stdout(Stdio::null())
+        .status()
+        .expect("failed to run markdown_timesheet");
+    let elapsed = started.elapsed();
+    assert!(status.success(), "markdown_timesheet exited with {status}");
+    elapsed
+}
+
+fn main() {
+    let binary = env!("CARGO_BIN_EXE_markdown_timesheet");
+    let dir: PathBuf = std::env::temp_dir().join("markdown_timesheet_bench_corpus");
+    let file_count = generate_corpus(&dir);
+
+    // Warm the page cache so the first measured run is not an outlier
+    time_run(binary, &dir);
+
+    let mut timings: Vec<Duration> = (0..ITERATIONS).map(|_| time_run(binary, &dir)).collect();
+    timings.sort_unstable();
+
+    let median = timings[timings.len() / 2];
+    let best = timings[0];
+    #[allow(clippy::cast_precision_loss)]
+    let files_per_second = file_count as f64 / median.as_secs_f64();
+
+    println!("parse_directory: {file_count} files ({YEARS} years of daily files)");
+    println!("  best:   {best:?}");
+    println!("  median: {median:?}");
+    println!("  throughput: {files_per_second:.0} files/s");
+
+    let _ = fs::remove_dir_all(&dir);
+}
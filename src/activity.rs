@@ -0,0 +1,170 @@
+use crate::cli::LogArgs;
+use crate::duration::DurationFormat;
+use crate::{format_duration_with_flags, DaySummary};
+use chrono::NaiveDate;
+use std::fmt::Write as _;
+
+/// One session or "Work time" entry in the activity log
+#[derive(Debug, PartialEq)]
+pub struct LogRow {
+    pub date: NaiveDate,
+    pub start: String,
+    pub end: String,
+    pub duration: String,
+    pub project: String,
+    pub description: String,
+}
+
+/// Every session and "Work time" entry in the requested range, in the order
+/// they appear in each day's file
+pub fn log_rows(summaries: &[DaySummary], args: &LogArgs, durations: DurationFormat) -> Vec<LogRow> {
+    let in_range = |date: NaiveDate| args.from.is_none_or(|from| date >= from) && args.to.is_none_or(|to| date <= to);
+    let wanted = |project: Option<&str>| {
+        args.project
+            .as_deref()
+            .is_none_or(|wanted| project.is_some_and(|project| wanted.eq_ignore_ascii_case(project)))
+    };
+
+    let mut rows = Vec::new();
+    for day in summaries.iter().filter(|day| in_range(day.date)) {
+        let mut day_rows = Vec::new();
+        for entry in day.entries.iter().filter(|entry| wanted(entry.project())) {
+            let time = |time: Option<chrono::NaiveTime>| time.map_or_else(|| "-".to_string(), |time| time.format("%H:%M").to_string());
+            let duration = entry.duration().map_or_else(
+                || "E!".to_string(),
+                |duration| format_duration_with_flags(durations, duration, entry.tentative, false, false),
+            );
+            day_rows.push((entry.line, LogRow {
+                date: day.date,
+                start: time(entry.start_time),
+                end: time(entry.end_time),
+                duration,
+                project: entry.project().unwrap_or_default().to_string(),
+                description: entry.description.clone(),
+            }));
+        }
+        for entry in day.work_time_entries.iter().filter(|entry| wanted(entry.project())) {
+            day_rows.push((entry.line, LogRow {
+                date: day.date,
+                start: "-".to_string(),
+                end: "-".to_string(),
+                duration: format_duration_with_flags(durations, entry.duration, false, false, false),
+                project: entry.project().unwrap_or_default().to_string(),
+                description: entry.description.clone(),
+            }));
+        }
+        day_rows.sort_by_key(|(line, _)| *line);
+        rows.extend(day_rows.into_iter().map(|(_, row)| row));
+    }
+    rows
+}
+
+/// The activity log as an aligned text table
+pub fn render_log(rows: &[LogRow]) -> String {
+    let headers = ["Date", "Start", "End", "Duration", "Project", "Description"];
+    let cells: Vec<[String; 6]> = rows
+        .iter()
+        .map(|row| {
+            [
+                row.date.to_string(),
+                row.start.clone(),
+                row.end.clone(),
+                row.duration.clone(),
+                row.project.clone(),
+                row.description.clone(),
+            ]
+        })
+        .collect();
+
+    let mut widths = headers.map(str::len);
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let mut write_row = |row: &[&str]| {
+        let mut line = String::new();
+        for (index, cell) in row.iter().enumerate() {
+            if index + 1 == row.len() {
+                line.push_str(cell);
+            } else {
+                let _ = write!(line, "{cell:<width$}  ", width = widths[index]);
+            }
+        }
+        let _ = writeln!(out, "{}", line.trim_end());
+    };
+
+    write_row(&headers);
+    let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    write_row(&rule.iter().map(String::as_str).collect::<Vec<_>>());
+    for row in &cells {
+        write_row(&row.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::CommonArgs;
+    use crate::TimesheetParser;
+    use std::path::PathBuf;
+
+    fn args(project: Option<&str>) -> LogArgs {
+        LogArgs {
+            common: CommonArgs {
+                directory: PathBuf::from("."),
+                debug: false,
+                strict: false,
+                config: None,
+                duration_style: None,
+                precision: None,
+            },
+            from: None,
+            to: None,
+            project: project.map(str::to_string),
+        }
+    }
+
+    fn summaries() -> Vec<DaySummary> {
+        let content = "# Notes\n\nStart work 9:00 #acme fixing bugs\nHad a productive morning.\n\nStop work 12:00\n\nLunch break\n\nWork time 30 minutes #other code review\n- follow-up with Sam\nStart work 13:00\n";
+        vec![TimesheetParser::new(false).unwrap().parse_file(content, NaiveDate::from_ymd_opt(2025, 8, 25).unwrap()).unwrap()]
+    }
+
+    #[test]
+    fn test_log_rows() {
+        let rows = log_rows(&summaries(), &args(None), DurationFormat::default());
+        let rows: Vec<_> = rows
+            .iter()
+            .map(|row| (row.start.as_str(), row.end.as_str(), row.duration.as_str(), row.project.as_str(), row.description.as_str()))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("09:00", "12:00", "3h 00m", "acme", "fixing bugs; Had a productive morning."),
+                ("-", "-", "0h 30m", "other", "code review; follow-up with Sam"),
+                ("13:00", "-", "E!", "", ""),
+            ]
+        );
+
+        let rows = log_rows(&summaries(), &args(Some("ACME")), DurationFormat::default());
+        assert_eq!(rows.len(), 1);
+    }
+
+    #[test]
+    fn test_render_log() {
+        let rows = log_rows(&summaries(), &args(Some("acme")), DurationFormat::default());
+        let description = "fixing bugs; Had a productive morning.";
+        assert_eq!(
+            render_log(&rows),
+            format!(
+                "Date        Start  End    Duration  Project  Description\n\
+                 ----------  -----  -----  --------  -------  {}\n\
+                 2025-08-25  09:00  12:00  3h 00m    acme     {description}\n",
+                "-".repeat(description.len())
+            )
+        );
+    }
+}
@@ -0,0 +1,344 @@
+use crate::cli::{ChartArgs, ChartKind};
+use crate::config::Config;
+use crate::error::TimesheetError;
+use crate::invoice::escape_html;
+use crate::tui::heat_level;
+use crate::duration::DurationFormat;
+use crate::{get_month_name, hours_to_duration, DaySummary, TimesheetParser};
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+
+/// Fill colours of the heatmap levels, from nothing worked to the daily target reached
+const HEAT_COLORS: [&str; 5] = ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"];
+/// Project colours in order of total time; later projects share the last one
+const PROJECT_COLORS: [&str; 8] = ["#4e79a7", "#f28e2b", "#59a14f", "#e15759", "#76b7b2", "#edc948", "#b07aa1", "#9c9c9c"];
+const BAR_COLOR: &str = "#4c8c4a";
+const SHORT_COLOR: &str = "#d9822b";
+const TARGET_COLOR: &str = "#c0392b";
+
+/// Margins around the plot area of the bar charts
+const LEFT: f64 = 48.0;
+const TOP: f64 = 36.0;
+const BOTTOM: f64 = 28.0;
+const PLOT_HEIGHT: f64 = 200.0;
+
+fn hours(duration: Duration) -> f64 {
+    #[allow(clippy::cast_precision_loss)]
+    let minutes = duration.num_minutes() as f64;
+    minutes / 60.0
+}
+
+/// The `<svg>` element with a white background and a heading
+fn svg_start(out: &mut String, width: f64, height: f64, title: &str) {
+    let _ = writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width:.0}\" height=\"{height:.0}\" viewBox=\"0 0 {width:.0} {height:.0}\" font-family=\"sans-serif\" font-size=\"11\">"
+    );
+    let title = escape_html(title);
+    let _ = writeln!(out, "<title>{title}</title>\n<rect width=\"100%\" height=\"100%\" fill=\"#fff\"/>");
+    let _ = writeln!(out, "<text x=\"{LEFT}\" y=\"20\" font-size=\"14\" font-weight=\"bold\">{title}</text>");
+}
+
+/// A bar with a tooltip
+fn bar(out: &mut String, (x, y, width, height): (f64, f64, f64, f64), fill: &str, tooltip: &str) {
+    let _ = writeln!(
+        out,
+        "<rect x=\"{x:.1}\" y=\"{y:.1}\" width=\"{width:.1}\" height=\"{height:.1}\" fill=\"{fill}\"><title>{}</title></rect>",
+        escape_html(tooltip)
+    );
+}
+
+/// Horizontal grid lines and hour labels up to `max` hours; returns pixels per hour
+fn hour_axis(out: &mut String, max: f64, width: f64) -> f64 {
+    let step = [1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0].into_iter().find(|step| max / step <= 6.0).unwrap_or(1000.0);
+    let top = (max / step).ceil().max(1.0) * step;
+    let scale = PLOT_HEIGHT / top;
+    let mut tick = 0.0;
+    while tick <= top {
+        let y = TOP + PLOT_HEIGHT - tick * scale;
+        let _ = writeln!(out, "<line x1=\"{LEFT}\" x2=\"{width:.1}\" y1=\"{y:.1}\" y2=\"{y:.1}\" stroke=\"#e5e5e5\"/>");
+        let _ = writeln!(out, "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\" fill=\"#666\">{tick}h</text>", LEFT - 6.0, y + 4.0);
+        tick += step;
+    }
+    scale
+}
+
+/// Label under a bar or column
+fn x_label(out: &mut String, x: f64, y: f64, text: &str) {
+    let _ = writeln!(out, "<text x=\"{x:.1}\" y=\"{y:.1}\" text-anchor=\"middle\" fill=\"#666\">{}</text>", escape_html(text));
+}
+
+/// Weekly totals as bars against a dashed target line; weeks short of the
+/// target are orange and weeks without files show as gaps
+pub fn weekly_chart(summaries: &[DaySummary], week_start: Weekday, target: Duration, iso_weeks: bool, durations: DurationFormat) -> String {
+    let weeks = TimesheetParser::group_by_week(summaries, week_start);
+    let (Some(first), Some(last)) = (weeks.first(), weeks.last()) else {
+        return empty_chart("Weekly hours");
+    };
+    let totals: HashMap<_, _> = weeks.iter().map(|week| (week.week_start, week)).collect();
+    let starts: Vec<_> = first.week_start.iter_weeks().take_while(|start| *start <= last.week_start).collect();
+
+    let step = 18.0;
+    #[allow(clippy::cast_precision_loss)]
+    let width = LEFT + step * starts.len() as f64 + 16.0;
+    let height = TOP + PLOT_HEIGHT + BOTTOM;
+    let mut out = String::new();
+    svg_start(&mut out, width, height, "Weekly hours");
+
+    let max = weeks.iter().map(|week| hours(week.total_duration)).fold(hours(target), f64::max);
+    let scale = hour_axis(&mut out, max, width);
+    let label_every = 3;
+    for (index, start) in starts.iter().enumerate() {
+        #[allow(clippy::cast_precision_loss)]
+        let x = LEFT + step * index as f64;
+        if let Some(week) = totals.get(start) {
+            let bar_height = hours(week.total_duration) * scale;
+            let short = week.total_duration < target;
+            let mut tooltip = format!("{}: {}", week.label(iso_weeks), durations.format(week.total_duration));
+            if short {
+                let _ = write!(tooltip, " ({} short)", durations.format(target - week.total_duration));
+            }
+            let fill = if short { SHORT_COLOR } else { BAR_COLOR };
+            bar(&mut out, (x + 2.0, TOP + PLOT_HEIGHT - bar_height, step - 4.0, bar_height), fill, &tooltip);
+        }
+        if index % label_every == 0 {
+            let label = if iso_weeks { format!("W{:02}", (*start + Duration::days(3)).iso_week().week()) } else { start.format("%m-%d").to_string() };
+            x_label(&mut out, x + step / 2.0, TOP + PLOT_HEIGHT + 16.0, &label);
+        }
+    }
+
+    if target > Duration::zero() {
+        let y = TOP + PLOT_HEIGHT - hours(target) * scale;
+        let _ = writeln!(
+            out,
+            "<line x1=\"{LEFT}\" x2=\"{width:.1}\" y1=\"{y:.1}\" y2=\"{y:.1}\" stroke=\"{TARGET_COLOR}\" stroke-width=\"1.5\" stroke-dasharray=\"5 3\"><title>Target {}</title></line>",
+            durations.format(target)
+        );
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+/// GitHub-style calendar: one column per week and one square per day from
+/// `from` to `to`, shaded by hours against the daily target
+pub fn heatmap(summaries: &[DaySummary], from: NaiveDate, to: NaiveDate, week_start: Weekday, daily_target: Duration, durations: DurationFormat) -> String {
+    let cell = 11.0;
+    let pitch = 13.0;
+    let left = 36.0;
+    let top = TOP + 14.0;
+
+    let totals: HashMap<_, _> = summaries.iter().map(|day| (day.date, day.total_duration)).collect();
+    let first_column = from.week(week_start).first_day();
+    let columns: Vec<_> = first_column.iter_weeks().take_while(|start| *start <= to).collect();
+
+    #[allow(clippy::cast_precision_loss)]
+    let width = left + pitch * columns.len() as f64 + 16.0;
+    let height = top + pitch * 7.0 + 30.0;
+    let mut out = String::new();
+    svg_start(&mut out, width.max(300.0), height, "Daily hours");
+
+    let mut weekday = week_start;
+    for row in 0..7 {
+        if row % 2 == 1 {
+            let _ = writeln!(out, "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\" fill=\"#666\" font-size=\"9\">{weekday}</text>", left - 4.0, top + pitch * f64::from(row) + 9.0);
+        }
+        weekday = weekday.succ();
+    }
+
+    for (column, start) in columns.iter().enumerate() {
+        #[allow(clippy::cast_precision_loss)]
+        let x = left + pitch * column as f64;
+        for (row, date) in start.iter_days().take(7).enumerate() {
+            if date < from || date > to {
+                continue;
+            }
+            if date.day() == 1 {
+                let _ = writeln!(out, "<text x=\"{x:.1}\" y=\"{:.1}\" fill=\"#666\" font-size=\"9\">{:.3}</text>", top - 4.0, get_month_name(date.month()));
+            }
+            let worked = totals.get(&date).copied().unwrap_or_default();
+            #[allow(clippy::cast_precision_loss)]
+            let y = top + pitch * row as f64;
+            let tooltip = format!("{} {date}: {}", date.format("%a"), durations.format(worked));
+            bar(&mut out, (x, y, cell, cell), HEAT_COLORS[heat_level(worked, daily_target)], &tooltip);
+        }
+    }
+
+    let legend_y = top + pitch * 7.0 + 8.0;
+    let _ = writeln!(out, "<text x=\"{left}\" y=\"{:.1}\" fill=\"#666\" font-size=\"9\">Less</text>", legend_y + 9.0);
+    for (index, color) in HEAT_COLORS.iter().enumerate() {
+        #[allow(clippy::cast_precision_loss)]
+        let x = left + 26.0 + pitch * index as f64;
+        let _ = writeln!(out, "<rect x=\"{x:.1}\" y=\"{legend_y:.1}\" width=\"{cell}\" height=\"{cell}\" fill=\"{color}\"/>");
+    }
+    let _ = writeln!(out, "<text x=\"{:.1}\" y=\"{:.1}\" fill=\"#666\" font-size=\"9\">More</text>", left + 30.0 + pitch * 5.0, legend_y + 9.0);
+    out.push_str("</svg>\n");
+    out
+}
+
+/// Time per project and month as stacked bars, largest projects at the
+/// bottom; leave has no project and is not shown
+pub fn project_chart(summaries: &[DaySummary], durations: DurationFormat) -> String {
+    let months = TimesheetParser::group_by_month(summaries);
+    if months.is_empty() {
+        return empty_chart("Hours per project");
+    }
+
+    let mut by_month: HashMap<(i32, u32), HashMap<Option<&str>, Duration>> = HashMap::new();
+    let mut overall: HashMap<Option<&str>, Duration> = HashMap::new();
+    for day in summaries {
+        let month = by_month.entry((day.date.year(), day.date.month())).or_default();
+        let entries = day.entries.iter().map(|entry| (entry.project(), entry.duration().unwrap_or_default()));
+        let work_time = day.work_time_entries.iter().map(|entry| (entry.project(), entry.duration));
+        for (project, duration) in entries.chain(work_time) {
+            *month.entry(project).or_default() += duration;
+            *overall.entry(project).or_default() += duration;
+        }
+    }
+    let mut projects: Vec<_> = overall.into_iter().filter(|(_, total)| *total > Duration::zero()).collect();
+    projects.sort_unstable_by(|(a_project, a_total), (b_project, b_total)| b_total.cmp(a_total).then(a_project.cmp(b_project)));
+    let color = |index: usize| PROJECT_COLORS[index.min(PROJECT_COLORS.len() - 1)];
+    let name = |project: Option<&str>| project.map_or_else(|| "(untagged)".to_string(), |project| format!("#{project}"));
+
+    let step = 48.0;
+    #[allow(clippy::cast_precision_loss)]
+    let plot_width = step * months.len() as f64;
+    let width = LEFT + plot_width + 150.0;
+    #[allow(clippy::cast_precision_loss)]
+    let legend_height = 16.0 * projects.len().min(PROJECT_COLORS.len()) as f64;
+    let height = TOP + PLOT_HEIGHT.max(legend_height) + BOTTOM;
+    let mut out = String::new();
+    svg_start(&mut out, width, height, "Hours per project");
+
+    let stacked = |month: &(i32, u32)| by_month.get(month).map_or(0.0, |totals| totals.values().copied().map(hours).sum::<f64>());
+    let max = months.iter().map(|month| stacked(&(month.year, month.month))).fold(0.0, f64::max);
+    let scale = hour_axis(&mut out, max, LEFT + plot_width);
+
+    for (index, month) in months.iter().enumerate() {
+        #[allow(clippy::cast_precision_loss)]
+        let x = LEFT + step * index as f64;
+        let mut y = TOP + PLOT_HEIGHT;
+        let totals = by_month.get(&(month.year, month.month));
+        for (position, (project, _)) in projects.iter().enumerate() {
+            let Some(duration) = totals.and_then(|totals| totals.get(project)).filter(|duration| **duration > Duration::zero()) else {
+                continue;
+            };
+            let bar_height = hours(*duration) * scale;
+            y -= bar_height;
+            let tooltip = format!("{} {}: {} {}", get_month_name(month.month), month.year, name(*project), durations.format(*duration));
+            bar(&mut out, (x + 6.0, y, step - 12.0, bar_height), color(position), &tooltip);
+        }
+        x_label(&mut out, x + step / 2.0, TOP + PLOT_HEIGHT + 16.0, &format!("{:.3} {}", get_month_name(month.month), month.year % 100));
+    }
+
+    let legend_x = LEFT + plot_width + 16.0;
+    for (position, (project, total)) in projects.iter().enumerate().take(PROJECT_COLORS.len()) {
+        #[allow(clippy::cast_precision_loss)]
+        let y = TOP + 16.0 * position as f64;
+        let label = if position + 1 == PROJECT_COLORS.len() && projects.len() > PROJECT_COLORS.len() {
+            "other projects".to_string()
+        } else {
+            format!("{} ({})", name(*project), durations.format(*total))
+        };
+        let _ = writeln!(out, "<rect x=\"{legend_x:.1}\" y=\"{y:.1}\" width=\"10\" height=\"10\" fill=\"{}\"/>", color(position));
+        let _ = writeln!(out, "<text x=\"{:.1}\" y=\"{:.1}\">{}</text>", legend_x + 14.0, y + 9.0, escape_html(&label));
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+fn empty_chart(title: &str) -> String {
+    let mut out = String::new();
+    svg_start(&mut out, 300.0, 60.0, title);
+    let _ = writeln!(out, "<text x=\"{LEFT}\" y=\"44\" fill=\"#666\">No data</text>\n</svg>");
+    out
+}
+
+/// Render the requested chart for `--from` to `--to`, by default the last 52 weeks
+pub fn run(args: &ChartArgs, config: &Config, durations: DurationFormat, summaries: &[DaySummary]) -> Result<(), TimesheetError> {
+    let to = args.to.unwrap_or_else(|| Local::now().date_naive());
+    let from = args.from.unwrap_or(to - Duration::weeks(52) + Duration::days(1));
+    let summaries: Vec<_> = summaries.iter().filter(|day| day.date >= from && day.date <= to).cloned().collect();
+    let weekly_target = hours_to_duration(args.target.weekly_hours);
+
+    let svg = match args.kind {
+        ChartKind::Weekly => weekly_chart(&summaries, args.week.week_start, weekly_target, args.week.iso_weeks, durations),
+        ChartKind::Heatmap => heatmap(&summaries, from, to, args.week.week_start, config.schedule.daily_target(weekly_target), durations),
+        ChartKind::Projects => project_chart(&summaries, durations),
+    };
+
+    match &args.output {
+        Some(path) => {
+            fs::write(path, svg).map_err(|error| TimesheetError::io(path, error))?;
+            eprintln!("Chart written to {}", path.display());
+        }
+        None => print!("{svg}"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, month, day).unwrap()
+    }
+
+    fn summaries() -> Vec<DaySummary> {
+        let parser = TimesheetParser::new(false).unwrap();
+        vec![
+            parser.parse_file("Start work 9:00 #acme\nStop work 17:00", date(9, 1)).unwrap(),
+            parser.parse_file("Start work 9:00 #acme\nStop work 12:00\nWork time 2 hours #beta", date(9, 2)).unwrap(),
+            parser.parse_file("Start work 8:00\nStop work 18:00", date(9, 22)).unwrap(),
+            parser.parse_file("Work time 30 minutes #beta", date(10, 1)).unwrap(),
+        ]
+    }
+
+    /// The `<title>` tooltips of every bar or cell, in drawing order
+    fn tooltips(svg: &str) -> Vec<&str> {
+        svg.split("<rect ").skip(1).filter_map(|rect| rect.split_once("<title>")?.1.split_once("</title>").map(|(title, _)| title)).collect()
+    }
+
+    #[test]
+    fn test_weekly_chart() {
+        let svg = weekly_chart(&summaries(), Weekday::Mon, Duration::hours(10), false, DurationFormat::default());
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(tooltips(&svg), [
+            "Week of 2025-09-01 - 2025-09-07: 13h 00m",
+            "Week of 2025-09-22 - 2025-09-28: 10h 00m",
+            "Week of 2025-09-29 - 2025-10-05: 0h 30m (9h 30m short)",
+        ]);
+        // Four weeks from Sep 1 to Sep 29, the two empty ones left as gaps
+        assert_eq!(svg.matches("<text").count() - svg.matches("h</text>").count(), 1 + 2);
+        assert!(svg.contains("<title>Target 10h 00m</title>"));
+        assert!(weekly_chart(&[], Weekday::Mon, Duration::hours(10), false, DurationFormat::default()).contains("No data"));
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let svg = heatmap(&summaries(), date(9, 1), date(9, 30), Weekday::Mon, Duration::hours(8), DurationFormat::default());
+        let cells = tooltips(&svg);
+        assert_eq!(cells.len(), 30);
+        assert_eq!(cells[0], "Mon 2025-09-01: 8h 00m");
+        assert!(svg.contains(&format!("fill=\"{}\"><title>Mon 2025-09-01", HEAT_COLORS[4])));
+        assert!(svg.contains(&format!("fill=\"{}\"><title>Tue 2025-09-02", HEAT_COLORS[2])));
+        assert!(svg.contains(&format!("fill=\"{}\"><title>Wed 2025-09-03", HEAT_COLORS[0])));
+        assert!(svg.contains(">Sep</text>"));
+    }
+
+    #[test]
+    fn test_project_chart() {
+        let svg = project_chart(&summaries(), DurationFormat::default());
+        assert_eq!(tooltips(&svg), [
+            "September 2025: #acme 11h 00m",
+            "September 2025: (untagged) 10h 00m",
+            "September 2025: #beta 2h 00m",
+            "October 2025: #beta 0h 30m",
+        ]);
+        assert!(svg.contains(">#acme (11h 00m)</text>"));
+        assert!(svg.contains(">Sep 25</text>") && svg.contains(">Oct 25</text>"));
+    }
+}
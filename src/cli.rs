@@ -0,0 +1,766 @@
+use crate::duration::{DurationStyle, MAX_PRECISION};
+use crate::error::TimesheetError;
+use crate::PayPeriod;
+use chrono::{NaiveDate, NaiveTime, Weekday};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use std::path::PathBuf;
+
+/// Process markdown timesheet files into daily, weekly and monthly summaries
+///
+/// Running without a subcommand is the same as `report`.
+#[derive(Debug, Parser)]
+#[command(name = "markdown_timesheet", version, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub legacy: LegacyArgs,
+}
+
+impl Cli {
+    /// The subcommand to run, treating a bare invocation as `report` (or `status` with `--summarize`)
+    pub fn into_command(self) -> Command {
+        self.command.unwrap_or_else(|| {
+            let LegacyArgs {
+                common,
+                target,
+                week,
+                pay,
+                sections,
+                summarize,
+            } = self.legacy;
+            if summarize {
+                Command::Status(StatusArgs {
+                    common,
+                    target,
+                    week,
+                    pay,
+                    current: CurrentPeriod::Week,
+                })
+            } else {
+                Command::Report(ReportArgs {
+                    common,
+                    target,
+                    week,
+                    pay,
+                    sections,
+                    format: ReportFormat::Text,
+                })
+            }
+        })
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print the daily, monthly and weekly summaries (default)
+    Report(ReportArgs),
+    /// Print a one-line summary of today and the current week for status bars
+    Status(StatusArgs),
+    /// List every session with its times, project and description
+    Log(LogArgs),
+    /// List problems in the timesheet files and exit non-zero if any are found
+    Check(CheckArgs),
+    /// Browse days, weeks and problems in an interactive terminal dashboard
+    Tui(TuiArgs),
+    /// Serve a JSON API and a live HTML dashboard of the timesheets
+    Serve(ServeArgs),
+    /// Draw weekly hours, a daily heatmap or project totals as an SVG image
+    Chart(ChartArgs),
+    /// Flag days and weeks that break the configured working-time rules
+    Compliance(ComplianceArgs),
+    /// Repair open sessions, 12-hour times, non-canonical entries and duplicates
+    Fix(FixArgs),
+    /// Rewrite entries into the canonical form, leaving other text untouched
+    Fmt(FmtArgs),
+    /// Append a "Start work" entry to today's file
+    Start(ClockArgs),
+    /// Append a "Stop work" entry to today's file
+    Stop(ClockArgs),
+    /// Export daily totals in a machine-readable format
+    Export(ExportArgs),
+    /// Generate an invoice for a client's billable hours
+    Invoice(InvoiceArgs),
+    /// Merge sessions from another time tracker into the daily files
+    Import(ImportArgs),
+    /// Print a shell completion script to stdout
+    Completions(CompletionsArgs),
+    /// Print the man page in roff format to stdout
+    Man,
+}
+
+impl Command {
+    /// Options of subcommands that read a timesheet directory and its configuration
+    pub fn common(&self) -> Option<&CommonArgs> {
+        match self {
+            Self::Report(ReportArgs { common, .. })
+            | Self::Status(StatusArgs { common, .. })
+            | Self::Log(LogArgs { common, .. })
+            | Self::Check(CheckArgs { common })
+            | Self::Tui(TuiArgs { common, .. })
+            | Self::Serve(ServeArgs { common, .. })
+            | Self::Chart(ChartArgs { common, .. })
+            | Self::Compliance(ComplianceArgs { common, .. })
+            | Self::Fix(FixArgs { common, .. })
+            | Self::Fmt(FmtArgs { common, .. })
+            | Self::Export(ExportArgs {
+                format:
+                    ExportFormat::Csv(common)
+                    | ExportFormat::Json(common)
+                    | ExportFormat::Timewarrior(common)
+                    | ExportFormat::Ics(common),
+            })
+            | Self::Invoice(InvoiceArgs { common, .. })
+            | Self::Import(ImportArgs {
+                source:
+                    ImportSource::Timewarrior(ImportFileArgs { common, .. })
+                    | ImportSource::Toggl(CsvImportArgs {
+                        input: ImportFileArgs { common, .. },
+                        ..
+                    })
+                    | ImportSource::Clockify(CsvImportArgs {
+                        input: ImportFileArgs { common, .. },
+                        ..
+                    }),
+            }) => Some(common),
+            Self::Start(_) | Self::Stop(_) | Self::Completions(_) | Self::Man => None,
+        }
+    }
+}
+
+/// Options shared by every subcommand that reads a timesheet directory
+#[derive(Debug, Args)]
+pub struct CommonArgs {
+    /// Directory containing markdown timesheet files
+    #[arg(default_value = ".")]
+    pub directory: PathBuf,
+
+    /// Show detailed debug information and error locations
+    #[arg(long)]
+    pub debug: bool,
+
+    /// Fail on the first unreadable or unparsable file instead of skipping it
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Configuration file (default: timesheet.toml in the timesheet directory)
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// How durations are written (default: hm, or [format] duration in the configuration)
+    #[arg(long, value_enum, value_name = "STYLE")]
+    pub duration_style: Option<DurationStyle>,
+
+    /// Decimal places for decimal hours (default: 2)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(..=i64::from(MAX_PRECISION)))]
+    pub precision: Option<u8>,
+}
+
+#[derive(Debug, Args)]
+pub struct TargetArgs {
+    /// Expected weekly work hours
+    #[arg(long, value_name = "HOURS", default_value = "40", value_parser = parse_weekly_hours)]
+    pub weekly_hours: f64,
+}
+
+/// How days are grouped into weeks and how weeks are labelled
+#[derive(Debug, Args)]
+pub struct WeekArgs {
+    /// First day of the week, e.g. monday, sunday or saturday
+    #[arg(long, value_name = "DAY", default_value = "monday", value_parser = parse_weekday)]
+    pub week_start: Weekday,
+
+    /// Label weeks by ISO 8601 week number (e.g. 2025-W35)
+    #[arg(long)]
+    pub iso_weeks: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PayPeriodKind {
+    /// Fourteen-day periods counted from --pay-period-anchor
+    Biweekly,
+    /// The 1st to the 15th and the 16th to the end of each month
+    SemiMonthly,
+}
+
+/// Payroll or invoicing periods reported alongside weeks and months
+#[derive(Debug, Args)]
+pub struct PayPeriodArgs {
+    /// Add a pay period section using this period type
+    #[arg(long, value_enum, value_name = "TYPE", requires_if("biweekly", "pay_period_anchor"))]
+    pub pay_period: Option<PayPeriodKind>,
+
+    /// Any date a biweekly pay period starts on
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    pub pay_period_anchor: Option<NaiveDate>,
+}
+
+impl PayPeriodArgs {
+    pub fn pay_period(&self) -> Option<PayPeriod> {
+        match (self.pay_period?, self.pay_period_anchor) {
+            (PayPeriodKind::Biweekly, Some(anchor)) => Some(PayPeriod::Biweekly { anchor }),
+            (PayPeriodKind::Biweekly, None) => None,
+            (PayPeriodKind::SemiMonthly, _) => Some(PayPeriod::SemiMonthly),
+        }
+    }
+}
+
+/// Which period the status bar measures against its target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CurrentPeriod {
+    Week,
+    PayPeriod,
+}
+
+/// Optional report sections printed after the default ones
+#[derive(Debug, Args)]
+pub struct SectionArgs {
+    /// Add a quarterly summary with expected hours, leave and averages
+    #[arg(long)]
+    pub quarterly: bool,
+
+    /// Add a yearly summary with expected hours, leave and averages
+    #[arg(long)]
+    pub yearly: bool,
+
+    #[command(flatten)]
+    pub bars: BarArgs,
+}
+
+/// Bars next to the daily and weekly totals of the text report
+#[derive(Debug, Args)]
+pub struct BarArgs {
+    /// Draw a bar against the expected hours next to each daily and weekly total (text format)
+    #[arg(long = "bars")]
+    pub enabled: bool,
+
+    /// Draw bars with plain ASCII characters only
+    #[arg(long, requires = "enabled")]
+    pub no_unicode: bool,
+}
+
+/// Options accepted when no subcommand is given, kept for existing scripts
+#[derive(Debug, Args)]
+pub struct LegacyArgs {
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    #[command(flatten)]
+    pub target: TargetArgs,
+
+    #[command(flatten)]
+    pub week: WeekArgs,
+
+    #[command(flatten)]
+    pub pay: PayPeriodArgs,
+
+    #[command(flatten)]
+    pub sections: SectionArgs,
+
+    /// Show compact current day and week summary for status bar (same as `status`)
+    #[arg(long)]
+    pub summarize: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ReportArgs {
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    #[command(flatten)]
+    pub target: TargetArgs,
+
+    #[command(flatten)]
+    pub week: WeekArgs,
+
+    #[command(flatten)]
+    pub pay: PayPeriodArgs,
+
+    #[command(flatten)]
+    pub sections: SectionArgs,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    pub format: ReportFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// Plain text sections for the terminal
+    Text,
+    /// Markdown tables for wikis and chat
+    Markdown,
+    /// A self-contained HTML page with shortages highlighted
+    Html,
+}
+
+#[derive(Debug, Args)]
+pub struct StatusArgs {
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    #[command(flatten)]
+    pub target: TargetArgs,
+
+    #[command(flatten)]
+    pub week: WeekArgs,
+
+    #[command(flatten)]
+    pub pay: PayPeriodArgs,
+
+    /// Period shown next to today's total
+    #[arg(long, value_enum, default_value = "week", requires_if("pay-period", "pay_period"))]
+    pub current: CurrentPeriod,
+}
+
+#[derive(Debug, Args)]
+pub struct LogArgs {
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    /// First day to list
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    pub from: Option<NaiveDate>,
+
+    /// Last day to list
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    pub to: Option<NaiveDate>,
+
+    /// Only list sessions tagged with this project
+    #[arg(long, value_name = "TAG")]
+    pub project: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct CheckArgs {
+    #[command(flatten)]
+    pub common: CommonArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct ComplianceArgs {
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    #[command(flatten)]
+    pub week: WeekArgs,
+
+    /// First day to check
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    pub from: Option<NaiveDate>,
+
+    /// Last day to check
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Args)]
+pub struct FixArgs {
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    /// Close the open session on --date with a stop at this time
+    #[arg(long, value_name = "HH:MM", value_parser = parse_clock_time, requires = "date")]
+    pub close_at: Option<NaiveTime>,
+
+    /// Day of the session to close
+    #[arg(long, value_name = "YYYY-MM-DD", requires = "close_at")]
+    pub date: Option<NaiveDate>,
+
+    /// Line of the start entry to close, when the day has more than one open session
+    #[arg(long, value_name = "LINE", requires = "date")]
+    pub line: Option<usize>,
+
+    /// Write the repairs without asking for confirmation
+    #[arg(long)]
+    pub apply: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct TuiArgs {
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    #[command(flatten)]
+    pub target: TargetArgs,
+
+    #[command(flatten)]
+    pub week: WeekArgs,
+
+    /// Seconds between rescans of the directory, which also update today's running session
+    #[arg(long, value_name = "SECONDS", default_value = "30", value_parser = clap::value_parser!(u64).range(1..))]
+    pub refresh: u64,
+}
+
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    #[command(flatten)]
+    pub target: TargetArgs,
+
+    #[command(flatten)]
+    pub week: WeekArgs,
+
+    /// Address and port to listen on
+    #[arg(long, value_name = "ADDRESS:PORT", default_value = "127.0.0.1:8080")]
+    pub bind: std::net::SocketAddr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ChartKind {
+    /// Weekly totals against the weekly target
+    Weekly,
+    /// A calendar of daily hours, one square per day
+    Heatmap,
+    /// Hours per project and month, stacked
+    Projects,
+}
+
+#[derive(Debug, Args)]
+pub struct ChartArgs {
+    /// Which chart to draw
+    #[arg(value_enum)]
+    pub kind: ChartKind,
+
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    #[command(flatten)]
+    pub target: TargetArgs,
+
+    #[command(flatten)]
+    pub week: WeekArgs,
+
+    /// First day to chart (default: 52 weeks before --to)
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    pub from: Option<NaiveDate>,
+
+    /// Last day to chart (default: today)
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    pub to: Option<NaiveDate>,
+
+    /// Write the SVG to this file instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct FmtArgs {
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    /// Show what would change and exit non-zero instead of writing
+    #[arg(long)]
+    pub check: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ClockArgs {
+    /// Directory containing markdown timesheet files
+    #[arg(default_value = ".")]
+    pub directory: PathBuf,
+
+    /// Time to record instead of the current time
+    #[arg(long, value_name = "HH:MM", value_parser = parse_clock_time)]
+    pub at: Option<NaiveTime>,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    #[command(subcommand)]
+    pub format: ExportFormat,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ExportFormat {
+    /// One CSV row per day
+    Csv(CommonArgs),
+    /// A JSON array with one object per day
+    Json(CommonArgs),
+    /// Sessions as `timew export` JSON, for `timew import`
+    Timewarrior(CommonArgs),
+    /// Sessions as iCalendar events, for calendar apps
+    Ics(CommonArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ImportArgs {
+    #[command(subcommand)]
+    pub source: ImportSource,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ImportSource {
+    /// JSON from `timew export`
+    Timewarrior(ImportFileArgs),
+    /// A Toggl Track detailed report exported as CSV
+    Toggl(CsvImportArgs),
+    /// A Clockify detailed report exported as CSV
+    Clockify(CsvImportArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ImportFileArgs {
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    /// File to import (default: standard input)
+    #[arg(long, value_name = "FILE")]
+    pub file: Option<PathBuf>,
+
+    /// Show the changes to each daily file as a diff without writing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct CsvImportArgs {
+    #[command(flatten)]
+    pub input: ImportFileArgs,
+
+    /// strftime format of the date columns, e.g. %d/%m/%Y (default: detect
+    /// YYYY-MM-DD, MM/DD/YYYY, DD.MM.YYYY or DD/MM/YYYY from all rows)
+    #[arg(long, value_name = "FORMAT")]
+    pub date_format: Option<String>,
+}
+
+/// How billable time is split into invoice line items
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LineItemGrouping {
+    /// One line per project per day
+    Day,
+    /// One line per session or "Work time" entry
+    Session,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InvoiceFormat {
+    Markdown,
+    Html,
+    /// HTML with A4 page setup, ready to print or save as PDF
+    PrintHtml,
+}
+
+#[derive(Debug, Args)]
+pub struct InvoiceArgs {
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    /// Client key from the [clients] section of the configuration
+    #[arg(long, value_name = "KEY")]
+    pub client: String,
+
+    /// Only bill this project (hashtag) of the client
+    #[arg(long, value_name = "TAG")]
+    pub project: Option<String>,
+
+    /// First day to bill
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    pub from: NaiveDate,
+
+    /// Last day to bill
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    pub to: NaiveDate,
+
+    /// Line item granularity
+    #[arg(long, value_enum, default_value = "day")]
+    pub by: LineItemGrouping,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "markdown")]
+    pub format: InvoiceFormat,
+
+    /// Write the invoice to this file instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Use this invoice number instead of the next one in the sequence
+    #[arg(long, value_name = "N")]
+    pub number: Option<u32>,
+
+    /// Mark the invoice as a draft and leave the number sequence untouched
+    #[arg(long)]
+    pub draft: bool,
+
+    /// Bill exact minutes even when a rounding rule is configured
+    #[arg(long)]
+    pub no_rounding: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct CompletionsArgs {
+    /// Shell to generate completions for
+    pub shell: Shell,
+}
+
+pub fn parse_weekly_hours(value: &str) -> Result<f64, TimesheetError> {
+    match value.parse::<f64>() {
+        Ok(hours) if hours.is_finite() && hours >= 0.0 => Ok(hours),
+        _ => Err(TimesheetError::config("weekly_hours", "expected a non-negative number of hours")),
+    }
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday, TimesheetError> {
+    value
+        .parse()
+        .map_err(|_| TimesheetError::config("week_start", "expected a day of the week such as monday or sun"))
+}
+
+fn parse_clock_time(value: &str) -> Result<NaiveTime, TimesheetError> {
+    NaiveTime::parse_from_str(value, "%H:%M")
+        .map_err(|_| TimesheetError::config("time", "expected a 24-hour time such as 9:00 or 17:30"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+    use std::process::ExitCode;
+
+    fn parse(args: &[&str]) -> Result<Command, clap::Error> {
+        Cli::try_parse_from(std::iter::once("markdown_timesheet").chain(args.iter().copied())).map(Cli::into_command)
+    }
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_bare_invocation_is_report() {
+        let Ok(Command::Report(args)) = parse(&["/tmp/timesheets", "--weekly-hours", "35"]) else {
+            panic!("expected report");
+        };
+        assert_eq!(args.common.directory, PathBuf::from("/tmp/timesheets"));
+        assert!((args.target.weekly_hours - 35.0).abs() < f64::EPSILON);
+
+        let Ok(Command::Report(args)) = parse(&[]) else {
+            panic!("expected report");
+        };
+        assert_eq!(args.common.directory, PathBuf::from("."));
+    }
+
+    #[test]
+    fn test_report_sections() {
+        let Ok(Command::Report(args)) = parse(&["report", "--quarterly"]) else {
+            panic!("expected report");
+        };
+        assert!(args.sections.quarterly);
+        assert!(!args.sections.yearly);
+
+        let Ok(Command::Report(args)) = parse(&["--yearly"]) else {
+            panic!("expected report");
+        };
+        assert!(args.sections.yearly);
+        assert!(!args.sections.bars.enabled);
+
+        let Ok(Command::Report(args)) = parse(&["--bars", "--no-unicode"]) else {
+            panic!("expected report");
+        };
+        assert!(args.sections.bars.enabled && args.sections.bars.no_unicode);
+        assert!(parse(&["report", "--no-unicode"]).is_err());
+    }
+
+    #[test]
+    fn test_week_options() {
+        let Ok(Command::Status(args)) = parse(&["--summarize", "--week-start", "Sunday", "--iso-weeks"]) else {
+            panic!("expected status");
+        };
+        assert_eq!(args.week.week_start, Weekday::Sun);
+        assert!(args.week.iso_weeks);
+
+        let Ok(Command::Report(args)) = parse(&["report", "--week-start", "sat"]) else {
+            panic!("expected report");
+        };
+        assert_eq!(args.week.week_start, Weekday::Sat);
+        assert!(!args.week.iso_weeks);
+
+        assert!(parse(&["--week-start", "someday"]).is_err());
+    }
+
+    #[test]
+    fn test_pay_period_options() {
+        let Ok(Command::Report(args)) = parse(&["--pay-period", "biweekly", "--pay-period-anchor", "2025-08-18"]) else {
+            panic!("expected report");
+        };
+        let anchor = NaiveDate::from_ymd_opt(2025, 8, 18).unwrap();
+        assert_eq!(args.pay.pay_period(), Some(PayPeriod::Biweekly { anchor }));
+
+        let Ok(Command::Status(args)) = parse(&["status", "--pay-period", "semi-monthly", "--current", "pay-period"]) else {
+            panic!("expected status");
+        };
+        assert_eq!(args.pay.pay_period(), Some(PayPeriod::SemiMonthly));
+        assert_eq!(args.current, CurrentPeriod::PayPeriod);
+
+        // Biweekly periods need an anchor, and the status bar needs a pay period to show
+        assert!(parse(&["--pay-period", "biweekly"]).is_err());
+        assert!(parse(&["status", "--current", "pay-period"]).is_err());
+    }
+
+    #[test]
+    fn test_summarize_is_status() {
+        assert!(matches!(parse(&["--summarize"]), Ok(Command::Status(_))));
+        assert!(matches!(parse(&["status", "--weekly-hours", "35"]), Ok(Command::Status(_))));
+    }
+
+    #[test]
+    fn test_unknown_flags_are_rejected() {
+        let error = parse(&["--sumarize"]).unwrap_err();
+        assert_eq!(error.kind(), clap::error::ErrorKind::UnknownArgument);
+
+        let error = parse(&["--weekly-hours", "abc"]).unwrap_err();
+        assert_eq!(error.kind(), clap::error::ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn test_parse_weekly_hours() {
+        assert!((parse_weekly_hours("37.5").unwrap() - 37.5).abs() < f64::EPSILON);
+        assert!(parse_weekly_hours("0").is_ok());
+
+        for invalid in ["abc", "-5", "NaN", "inf", ""] {
+            let error = parse_weekly_hours(invalid).unwrap_err();
+            assert!(matches!(error, TimesheetError::Config { .. }));
+            assert_eq!(error.exit_code(), ExitCode::from(78));
+        }
+    }
+
+    #[test]
+    fn test_invoice_options() {
+        let Ok(Command::Invoice(args)) = parse(&[
+            "invoice", "--client", "acme", "--from", "2025-08-01", "--to", "2025-08-31", "--by", "session", "--format", "print-html",
+        ]) else {
+            panic!("expected invoice");
+        };
+        assert_eq!(args.client, "acme");
+        assert_eq!(args.by, LineItemGrouping::Session);
+        assert_eq!(args.format, InvoiceFormat::PrintHtml);
+        assert!(!args.draft);
+
+        assert!(parse(&["invoice", "--client", "acme", "--from", "2025-08-01"]).is_err());
+    }
+
+    #[test]
+    fn test_duration_style_options() {
+        let Ok(Command::Status(args)) = parse(&["status", "--duration-style", "hh:mm", "--precision", "1"]) else {
+            panic!("expected status");
+        };
+        assert_eq!(args.common.duration_style, Some(DurationStyle::Clock));
+        assert_eq!(args.common.precision, Some(1));
+
+        assert!(parse(&["--precision", "7"]).is_err());
+        assert!(parse(&["--duration-style", "hours"]).is_err());
+    }
+
+    #[test]
+    fn test_clock_time() {
+        let Ok(Command::Start(args)) = parse(&["start", "--at", "8:30"]) else {
+            panic!("expected start");
+        };
+        assert_eq!(args.at, NaiveTime::from_hms_opt(8, 30, 0));
+        assert!(parse(&["stop", "--at", "25:00"]).is_err());
+    }
+}
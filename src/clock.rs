@@ -0,0 +1,102 @@
+use crate::cli::ClockArgs;
+use crate::error::TimesheetError;
+use crate::TimesheetParser;
+use chrono::{Local, NaiveTime, Timelike};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Clock {
+    Start,
+    Stop,
+}
+
+/// Append a start or stop entry to today's timesheet file, refusing to
+/// open a second session or close one that was never started.
+pub fn record(args: &ClockArgs, clock: Clock) -> Result<(), TimesheetError> {
+    let now = Local::now();
+    let today = now.date_naive();
+    let path = args.directory.join(format!("{today}.md"));
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(error) => return Err(TimesheetError::io(&path, error)),
+    };
+
+    let parser = TimesheetParser::new(false)?;
+    let summary = parser.parse_file(&content, today).map_err(|error| error.with_path(&path))?;
+    // Today's open session is the one that received tentative time
+    let session_open = summary.has_tentative;
+
+    match (clock, session_open) {
+        (Clock::Start, true) => {
+            return Err(TimesheetError::Usage(format!(
+                "a session is already open in {}; run `stop` first",
+                path.display()
+            )));
+        }
+        (Clock::Stop, false) => {
+            return Err(TimesheetError::Usage(format!("no open session to stop in {}", path.display())));
+        }
+        _ => {}
+    }
+
+    let time = args
+        .at
+        .unwrap_or_else(|| NaiveTime::from_hms_opt(now.hour(), now.minute(), 0).unwrap_or_default());
+    let entry = entry_line(clock, time);
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|error| TimesheetError::io(&path, error))?;
+    let separator = if content.is_empty() || content.ends_with('\n') { "" } else { "\n" };
+    writeln!(file, "{separator}{entry}").map_err(|error| TimesheetError::io(&path, error))?;
+
+    println!("{entry} recorded in {}", path.display());
+    Ok(())
+}
+
+pub fn entry_line(clock: Clock, time: NaiveTime) -> String {
+    let verb = match clock {
+        Clock::Start => "Start",
+        Clock::Stop => "Stop",
+    };
+    format!("{verb} work {}", time.format("%-H:%M"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_line() {
+        let time = NaiveTime::from_hms_opt(9, 5, 0).unwrap();
+        assert_eq!(entry_line(Clock::Start, time), "Start work 9:05");
+
+        let time = NaiveTime::from_hms_opt(17, 30, 0).unwrap();
+        assert_eq!(entry_line(Clock::Stop, time), "Stop work 17:30");
+    }
+
+    #[test]
+    fn test_record_refuses_double_start_and_stop() {
+        let directory = std::env::temp_dir().join(format!("markdown_timesheet_clock_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let at = |hour| ClockArgs {
+            directory: directory.clone(),
+            at: NaiveTime::from_hms_opt(hour, 0, 0),
+        };
+
+        record(&at(0), Clock::Start).unwrap();
+        assert!(matches!(record(&at(1), Clock::Start), Err(TimesheetError::Usage(_))));
+        record(&at(1), Clock::Stop).unwrap();
+        assert!(matches!(record(&at(2), Clock::Stop), Err(TimesheetError::Usage(_))));
+
+        let path = directory.join(format!("{}.md", Local::now().date_naive()));
+        let content = fs::read_to_string(path).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(content, "Start work 0:00\nStop work 1:00\n");
+    }
+}
@@ -0,0 +1,255 @@
+use crate::cli::ComplianceArgs;
+use crate::config::ComplianceConfig;
+use crate::duration::DurationFormat;
+use crate::{DaySummary, TimesheetParser, WeekSummary};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use std::fmt::Write as _;
+
+/// A break of a working-time rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// Less rest than required between the end of one shift and the start of the next
+    ShortRest { rest: Duration, minimum: Duration, since: NaiveDateTime },
+    /// Work without a long enough break for longer than allowed
+    NoBreak { from: NaiveDateTime, to: NaiveDateTime, limit: Duration },
+    /// More hours in a week than allowed
+    LongWeek { worked: Duration, maximum: Duration },
+}
+
+impl Violation {
+    /// The violation in words, with durations in the given format
+    pub fn describe(&self, durations: DurationFormat) -> String {
+        match self {
+            Self::ShortRest { rest, minimum, since } => format!(
+                "{} rest since {}, less than {}",
+                durations.format(*rest),
+                since.format("%a %H:%M"),
+                durations.format(*minimum)
+            ),
+            Self::NoBreak { from, to, limit } => format!(
+                "{} without a break from {} to {}, more than {}",
+                durations.format(*to - *from),
+                from.format("%H:%M"),
+                to.format("%H:%M"),
+                durations.format(*limit)
+            ),
+            Self::LongWeek { worked, maximum } => {
+                format!("{} over the {} weekly maximum", durations.format(*worked - *maximum), durations.format(*maximum))
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DayCompliance {
+    pub date: NaiveDate,
+    pub violations: Vec<Violation>,
+}
+
+#[derive(Debug)]
+pub struct WeekCompliance {
+    pub week: WeekSummary,
+    /// Hours worked, leaving out holiday and PTO credit
+    pub worked: Duration,
+    pub violation: Option<Violation>,
+    /// Number of daily violations in the week
+    pub day_violations: usize,
+}
+
+/// Sessions of one day as absolute times, in order of their start
+fn sessions(day: &DaySummary) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+    let mut sessions: Vec<_> = day
+        .entries
+        .iter()
+        .filter_map(|entry| {
+            let start = day.date.and_time(entry.start_time?);
+            Some((start, start + entry.duration()?))
+        })
+        .collect();
+    sessions.sort_unstable();
+    sessions
+}
+
+/// Stretches of work in which no pause reaches `min_break`
+fn work_blocks(sessions: &[(NaiveDateTime, NaiveDateTime)], min_break: Duration) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+    let mut blocks: Vec<(NaiveDateTime, NaiveDateTime)> = Vec::new();
+    for &(start, end) in sessions {
+        match blocks.last_mut() {
+            Some((_, block_end)) if start - *block_end < min_break => *block_end = (*block_end).max(end),
+            _ => blocks.push((start, end)),
+        }
+    }
+    blocks
+}
+
+/// Daily and weekly violations of `rules` for the days in the requested range.
+/// Rest before the first day in the range is measured from the day before it.
+pub fn evaluate(summaries: &[DaySummary], rules: &ComplianceConfig, args: &ComplianceArgs) -> (Vec<DayCompliance>, Vec<WeekCompliance>) {
+    let in_range = |date: NaiveDate| args.from.is_none_or(|from| date >= from) && args.to.is_none_or(|to| date <= to);
+
+    let mut days = Vec::new();
+    let mut previous_end: Option<NaiveDateTime> = None;
+    for day in summaries.iter().take_while(|day| args.to.is_none_or(|to| day.date <= to)) {
+        let sessions = sessions(day);
+        let mut violations = Vec::new();
+
+        if let (Some(minimum), Some(since), Some(&(start, _))) = (rules.min_rest(), previous_end, sessions.first()) {
+            let rest = start - since;
+            if rest >= Duration::zero() && rest < minimum {
+                violations.push(Violation::ShortRest { rest, minimum, since });
+            }
+        }
+        if let Some(limit) = rules.break_after() {
+            violations.extend(
+                work_blocks(&sessions, rules.min_break())
+                    .into_iter()
+                    .filter(|(from, to)| *to - *from > limit)
+                    .map(|(from, to)| Violation::NoBreak { from, to, limit }),
+            );
+        }
+
+        if let Some(end) = sessions.iter().map(|(_, end)| *end).max() {
+            previous_end = Some(end);
+        }
+        if in_range(day.date) {
+            days.push(DayCompliance { date: day.date, violations });
+        }
+    }
+
+    let summaries: Vec<_> = summaries.iter().filter(|day| in_range(day.date)).cloned().collect();
+    let weeks = TimesheetParser::group_by_week(&summaries, args.week.week_start)
+        .into_iter()
+        .map(|week| {
+            let worked = week.days.iter().map(|day| day.total_duration - day.leave_duration).sum();
+            let violation = rules
+                .max_week()
+                .filter(|maximum| worked > *maximum)
+                .map(|maximum| Violation::LongWeek { worked, maximum });
+            let day_violations = days
+                .iter()
+                .filter(|day| day.date >= week.week_start && day.date <= week.week_end())
+                .map(|day| day.violations.len())
+                .sum();
+            WeekCompliance { week, worked, violation, day_violations }
+        })
+        .collect();
+
+    (days, weeks)
+}
+
+fn rules_line(rules: &ComplianceConfig, durations: DurationFormat) -> String {
+    let mut parts = Vec::new();
+    if let Some(maximum) = rules.max_week() {
+        parts.push(format!("at most {} per week", durations.format(maximum)));
+    }
+    if let Some(minimum) = rules.min_rest() {
+        parts.push(format!("{} rest between shifts", durations.format(minimum)));
+    }
+    if let Some(limit) = rules.break_after() {
+        parts.push(format!("a {} break after {} of work", durations.format(rules.min_break()), durations.format(limit)));
+    }
+    if parts.is_empty() {
+        "Rules: none configured".to_string()
+    } else {
+        format!("Rules: {}", parts.join(", "))
+    }
+}
+
+pub fn render(days: &[DayCompliance], weeks: &[WeekCompliance], rules: &ComplianceConfig, iso_weeks: bool, durations: DurationFormat) -> String {
+    let mut out = rules_line(rules, durations);
+    out.push_str("\n\nDaily Violations:\n=================\n");
+    let mut day_count = 0;
+    for day in days {
+        for violation in &day.violations {
+            let _ = writeln!(out, "{} {:3}: {}", day.date, day.date.format("%a"), violation.describe(durations));
+            day_count += 1;
+        }
+    }
+    if day_count == 0 {
+        out.push_str("None\n");
+    }
+
+    out.push_str("\nWeekly Hours:\n=============\n");
+    for week in weeks {
+        let _ = write!(out, "{}: {}", week.week.label(iso_weeks), durations.format(week.worked));
+        let mut flags: Vec<_> = week.violation.iter().map(|violation| violation.describe(durations)).collect();
+        if week.day_violations > 0 {
+            let noun = if week.day_violations == 1 { "daily violation" } else { "daily violations" };
+            flags.push(format!("{} {noun}", week.day_violations));
+        }
+        if !flags.is_empty() {
+            let _ = write!(out, " [{}]", flags.join(", "));
+        }
+        out.push('\n');
+    }
+
+    let total = day_count + weeks.iter().filter(|week| week.violation.is_some()).count();
+    if total == 0 {
+        out.push_str("\nNo violations\n");
+    } else {
+        let noun = if total == 1 { "violation" } else { "violations" };
+        let _ = writeln!(out, "\n{total} {noun}");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Command};
+    use clap::Parser;
+
+    fn args() -> ComplianceArgs {
+        match Cli::parse_from(["markdown_timesheet", "compliance"]).into_command() {
+            Command::Compliance(args) => args,
+            command => panic!("unexpected command {command:?}"),
+        }
+    }
+
+    fn parse(day: u32, content: &str) -> DaySummary {
+        let date = NaiveDate::from_ymd_opt(2025, 9, day).unwrap();
+        TimesheetParser::new(false).unwrap().parse_file(content, date).unwrap()
+    }
+
+    #[test]
+    fn test_daily_violations() {
+        let summaries = [
+            parse(1, "Start work 14:00\nStop work 18:00\nStart work 18:30\nStop work 23:00"),
+            // 8h rest, then 7h with only a 15 minute pause
+            parse(2, "Start work 7:00\nStop work 10:00\nStart work 10:15\nStop work 14:00"),
+            parse(3, "Start work 9:00\nStop work 12:00\nStart work 12:30\nStop work 17:00"),
+        ];
+        let (days, weeks) = evaluate(&summaries, &ComplianceConfig::default(), &args());
+
+        assert!(days[0].violations.is_empty());
+        assert_eq!(days[1].violations.len(), 2);
+        assert_eq!(days[1].violations[0].describe(DurationFormat::default()), "8h 00m rest since Mon 23:00, less than 11h 00m");
+        assert_eq!(days[1].violations[1].describe(DurationFormat::default()), "7h 00m without a break from 07:00 to 14:00, more than 6h 00m");
+        assert!(days[2].violations.is_empty());
+        assert_eq!(weeks[0].day_violations, 2);
+        assert_eq!(weeks[0].violation, None);
+
+        // The short rest before the 2nd is found when the range starts there
+        let mut from_second = args();
+        from_second.from = NaiveDate::from_ymd_opt(2025, 9, 2);
+        let (days, weeks) = evaluate(&summaries, &ComplianceConfig::default(), &from_second);
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].violations[0].describe(DurationFormat::default()), "8h 00m rest since Mon 23:00, less than 11h 00m");
+        assert_eq!(weeks[0].worked, Duration::minutes(855));
+    }
+
+    #[test]
+    fn test_weekly_maximum() {
+        let summaries: Vec<_> = (1..=5).map(|day| parse(day, "Start work 7:00\nStop work 10:00\nStart work 11:00\nStop work 17:00\nWork time 1 hour")).collect();
+        let rules = ComplianceConfig::default();
+        let (days, weeks) = evaluate(&summaries, &rules, &args());
+        assert_eq!(weeks[0].worked, Duration::hours(50));
+        assert_eq!(weeks[0].violation.as_ref().unwrap().describe(DurationFormat::default()), "2h 00m over the 48h 00m weekly maximum");
+
+        let report = render(&days, &weeks, &rules, false, DurationFormat::default());
+        assert!(report.starts_with("Rules: at most 48h 00m per week, 11h 00m rest between shifts, a 0h 30m break after 6h 00m of work\n"));
+        assert!(report.contains("Daily Violations:\n=================\nNone\n"));
+        assert!(report.contains("Week of 2025-09-01 - 2025-09-07: 50h 00m [2h 00m over the 48h 00m weekly maximum]\n"));
+        assert!(report.ends_with("\n1 violation\n"));
+    }
+}
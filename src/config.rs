@@ -0,0 +1,437 @@
+use crate::duration::{DurationFormat, DurationStyle, MAX_PRECISION};
+use crate::error::TimesheetError;
+use crate::rounding::RoundingRule;
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// File name looked up in the timesheet directory when `--config` is not given
+pub const CONFIG_FILE_NAME: &str = "timesheet.toml";
+
+/// Settings read from `timesheet.toml`. Every section is optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub format: FormatConfig,
+    pub invoice: InvoiceConfig,
+    /// Billing increments applied to invoices; reports always use exact minutes
+    pub rounding: Option<RoundingRule>,
+    pub clients: BTreeMap<String, ClientConfig>,
+    pub holidays: HolidayConfig,
+    pub schedule: ScheduleConfig,
+    pub limits: LimitsConfig,
+    pub compliance: ComplianceConfig,
+    pub fmt: FmtConfig,
+}
+
+/// Output defaults, overridden by `--duration-style` and `--precision`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FormatConfig {
+    pub duration: Option<DurationStyle>,
+    pub precision: Option<u8>,
+}
+
+impl FormatConfig {
+    /// The configured format with command line overrides applied
+    pub fn resolve(&self, style: Option<DurationStyle>, precision: Option<u8>) -> Result<DurationFormat, TimesheetError> {
+        let precision = match precision.or(self.precision) {
+            Some(precision) if precision > MAX_PRECISION => {
+                return Err(TimesheetError::config(
+                    "format.precision",
+                    format!("expected at most {MAX_PRECISION} decimal places"),
+                ));
+            }
+            Some(precision) => precision,
+            None => DurationFormat::default().precision,
+        };
+        Ok(DurationFormat {
+            style: style.or(self.duration).unwrap_or_default(),
+            precision,
+        })
+    }
+}
+
+/// Public holidays credited as leave without a note in the day's file
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HolidayConfig {
+    /// An `.ics` calendar or a list of "YYYY-MM-DD Name" lines, relative to the timesheet directory
+    pub file: Option<PathBuf>,
+    /// Hours credited for each holiday, like a "Stat holiday" line
+    pub hours: f64,
+}
+
+impl Default for HolidayConfig {
+    fn default() -> Self {
+        Self { file: None, hours: 8.0 }
+    }
+}
+
+impl HolidayConfig {
+    pub fn credit(&self) -> Result<Duration, TimesheetError> {
+        if !(self.hours.is_finite() && (0.0..=24.0).contains(&self.hours)) {
+            return Err(TimesheetError::config("holidays.hours", "expected between 0 and 24 hours"));
+        }
+        Ok(crate::hours_to_duration(self.hours))
+    }
+}
+
+/// The days that are expected to have a timesheet file
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScheduleConfig {
+    pub workdays: Vec<Weekday>,
+    /// Minutes between sessions reported as an unexplained gap; 0 turns gap reports off
+    pub max_gap: u32,
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        Self {
+            workdays: vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri],
+            max_gap: 120,
+        }
+    }
+}
+
+impl ScheduleConfig {
+    pub fn is_workday(&self, date: NaiveDate) -> bool {
+        self.workdays.contains(&date.weekday())
+    }
+
+    pub fn max_gap(&self) -> Option<Duration> {
+        (self.max_gap > 0).then(|| Duration::minutes(i64::from(self.max_gap)))
+    }
+
+    /// The share of a weekly target expected on each scheduled workday
+    pub fn daily_target(&self, weekly: Duration) -> Duration {
+        weekly / i32::try_from(self.workdays.len()).unwrap_or(5).max(1)
+    }
+}
+
+/// Plausibility rules; entries outside them are reported, not dropped
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LimitsConfig {
+    /// Longest believable session in hours; 0 turns the rule off
+    pub max_session: f64,
+    /// Most hours worked in one day, leave excluded; 0 turns the rule off
+    pub max_day: f64,
+    #[serde(deserialize_with = "deserialize_clock_time")]
+    pub earliest_start: Option<NaiveTime>,
+    #[serde(deserialize_with = "deserialize_clock_time")]
+    pub latest_start: Option<NaiveTime>,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_session: 12.0,
+            max_day: 16.0,
+            earliest_start: None,
+            latest_start: None,
+        }
+    }
+}
+
+impl LimitsConfig {
+    pub fn max_session(&self) -> Option<Duration> {
+        positive_hours(self.max_session)
+    }
+
+    pub fn max_day(&self) -> Option<Duration> {
+        positive_hours(self.max_day)
+    }
+}
+
+/// The canonical entry form written by `fmt` and `fix`
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FmtConfig {
+    /// Text before a start time, e.g. "Start work"
+    pub start: String,
+    /// Text before a stop time, e.g. "Stop work"
+    pub stop: String,
+    /// Write "09:00" instead of "9:00"
+    pub zero_pad: bool,
+}
+
+impl Default for FmtConfig {
+    fn default() -> Self {
+        Self {
+            start: "Start work".to_string(),
+            stop: "Stop work".to_string(),
+            zero_pad: false,
+        }
+    }
+}
+
+/// Working-time rules checked by `compliance`. Each rule is off when set to 0.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ComplianceConfig {
+    /// Most hours worked in a week, leave excluded
+    pub max_week: f64,
+    /// Fewest hours between the end of one day's work and the start of the next
+    pub min_rest: f64,
+    /// Hours of work after which a break is required
+    pub break_after: f64,
+    /// Minutes a pause has to last to count as a break
+    pub min_break: u32,
+}
+
+impl Default for ComplianceConfig {
+    fn default() -> Self {
+        Self {
+            max_week: 48.0,
+            min_rest: 11.0,
+            break_after: 6.0,
+            min_break: 30,
+        }
+    }
+}
+
+impl ComplianceConfig {
+    pub fn max_week(&self) -> Option<Duration> {
+        positive_hours(self.max_week)
+    }
+
+    pub fn min_rest(&self) -> Option<Duration> {
+        positive_hours(self.min_rest)
+    }
+
+    pub fn break_after(&self) -> Option<Duration> {
+        positive_hours(self.break_after)
+    }
+
+    pub fn min_break(&self) -> Duration {
+        Duration::minutes(i64::from(self.min_break))
+    }
+}
+
+/// A limit given in hours, or `None` when it is turned off
+fn positive_hours(hours: f64) -> Option<Duration> {
+    (hours > 0.0).then(|| crate::hours_to_duration(hours))
+}
+
+/// A "HH:MM" time of day
+fn deserialize_clock_time<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<NaiveTime>, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    NaiveTime::parse_from_str(&text, "%H:%M")
+        .map(Some)
+        .map_err(|_| serde::de::Error::custom(format!("invalid time \"{text}\", expected HH:MM")))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InvoiceConfig {
+    /// Name and address printed in the "From" block, one line per line
+    pub issuer: Option<String>,
+    pub currency: String,
+    /// Hourly rate for clients and projects without their own
+    pub rate: Option<f64>,
+    pub number_prefix: String,
+    pub rounding: MoneyRounding,
+    pub taxes: Vec<TaxConfig>,
+}
+
+impl Default for InvoiceConfig {
+    fn default() -> Self {
+        Self {
+            issuer: None,
+            currency: "USD".to_string(),
+            rate: None,
+            number_prefix: "INV-".to_string(),
+            rounding: MoneyRounding::default(),
+            taxes: Vec::new(),
+        }
+    }
+}
+
+/// Where invoice amounts are rounded to whole cents
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MoneyRounding {
+    /// Round every line item, then add the rounded amounts
+    #[default]
+    PerLine,
+    /// Add the exact line amounts and round the subtotal once
+    Total,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaxConfig {
+    pub name: String,
+    /// Percentage applied to the subtotal, e.g. 5.0 for 5%
+    pub rate: f64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClientConfig {
+    pub name: Option<String>,
+    pub address: Option<String>,
+    pub rate: Option<f64>,
+    pub currency: Option<String>,
+    /// Project tags billed to this client; defaults to the client's key
+    pub projects: Vec<String>,
+    /// Hourly rates for individual projects, overriding `rate`
+    pub project_rates: BTreeMap<String, f64>,
+    /// Taxes for this client, replacing `[invoice] taxes`
+    pub taxes: Option<Vec<TaxConfig>>,
+    /// Billing increments for this client, replacing `[rounding]`
+    pub rounding: Option<RoundingRule>,
+}
+
+impl Config {
+    /// Load `path`, or `timesheet.toml` in `directory` when no path is given.
+    /// Only an explicitly requested file has to exist.
+    pub fn load(directory: &Path, path: Option<&Path>) -> Result<Self, TimesheetError> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => (directory.join(CONFIG_FILE_NAME), false),
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(content) => Self::parse(&content, &path),
+            Err(error) if !required && error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(TimesheetError::io(&path, error)),
+        }
+    }
+
+    fn parse(content: &str, path: &Path) -> Result<Self, TimesheetError> {
+        toml::from_str(content).map_err(|error| {
+            TimesheetError::config(path.display().to_string(), error.message().to_string())
+        })
+    }
+}
+
+impl ClientConfig {
+    pub fn projects(&self, key: &str) -> Vec<String> {
+        if self.projects.is_empty() {
+            vec![key.to_lowercase()]
+        } else {
+            self.projects.iter().map(|project| project.to_lowercase()).collect()
+        }
+    }
+}
+
+/// Where the invoice number sequence is kept for a timesheet directory
+pub fn invoice_sequence_path(directory: &Path) -> PathBuf {
+    directory.join(".invoice-sequence")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config = Config::parse(
+            r#"
+[invoice]
+issuer = "Jane Doe"
+currency = "CAD"
+rate = 95
+rounding = "total"
+taxes = [{ name = "GST", rate = 5 }]
+
+[clients.acme]
+name = "Acme Corp"
+projects = ["acme", "Acme-Web"]
+project_rates = { acme-web = 140.0 }
+"#,
+            Path::new("timesheet.toml"),
+        )
+        .unwrap();
+
+        assert_eq!(config.invoice.currency, "CAD");
+        assert_eq!(config.invoice.rounding, MoneyRounding::Total);
+        assert_eq!(config.invoice.number_prefix, "INV-");
+        assert_eq!(config.invoice.taxes[0].name, "GST");
+        let acme = &config.clients["acme"];
+        assert_eq!(acme.projects("acme"), ["acme", "acme-web"]);
+        assert_eq!(acme.project_rates["acme-web"], 140.0);
+    }
+
+    #[test]
+    fn test_format_config() {
+        let config = Config::parse("[format]\nduration = \"hh:mm\"\nprecision = 1", Path::new("timesheet.toml")).unwrap();
+        let format = config.format.resolve(None, None).unwrap();
+        assert_eq!(format, DurationFormat { style: DurationStyle::Clock, precision: 1 });
+
+        let format = config.format.resolve(Some(DurationStyle::Decimal), Some(3)).unwrap();
+        assert_eq!(format, DurationFormat { style: DurationStyle::Decimal, precision: 3 });
+
+        assert_eq!(Config::default().format.resolve(None, None).unwrap(), DurationFormat::default());
+
+        let config = Config::parse("[format]\nprecision = 9", Path::new("timesheet.toml")).unwrap();
+        assert!(matches!(config.format.resolve(None, None), Err(TimesheetError::Config { .. })));
+    }
+
+    #[test]
+    fn test_holiday_config() {
+        let config = Config::parse("[holidays]\nfile = \"holidays.ics\"", Path::new("timesheet.toml")).unwrap();
+        assert_eq!(config.holidays.file.as_deref(), Some(Path::new("holidays.ics")));
+        assert_eq!(config.holidays.credit().unwrap(), Duration::hours(8));
+        assert!(Config::default().holidays.file.is_none());
+
+        let config = Config::parse("[holidays]\nhours = 7.5", Path::new("timesheet.toml")).unwrap();
+        assert_eq!(config.holidays.credit().unwrap(), Duration::minutes(450));
+        let config = Config::parse("[holidays]\nhours = 30", Path::new("timesheet.toml")).unwrap();
+        assert!(matches!(config.holidays.credit(), Err(TimesheetError::Config { .. })));
+    }
+
+    #[test]
+    fn test_schedule_config() {
+        let config = Config::parse("[schedule]\nworkdays = [\"mon\", \"Tuesday\", \"Sat\"]\nmax_gap = 0", Path::new("timesheet.toml")).unwrap();
+        assert_eq!(config.schedule.workdays, [Weekday::Mon, Weekday::Tue, Weekday::Sat]);
+        assert!(config.schedule.is_workday(NaiveDate::from_ymd_opt(2025, 9, 6).unwrap()));
+        assert!(!config.schedule.is_workday(NaiveDate::from_ymd_opt(2025, 9, 3).unwrap()));
+        assert_eq!(config.schedule.max_gap(), None);
+        assert_eq!(Config::default().schedule.max_gap(), Some(Duration::hours(2)));
+    }
+
+    #[test]
+    fn test_limits_config() {
+        let config = Config::parse("[limits]\nmax_session = 10\nmax_day = 0\nearliest_start = \"06:30\"", Path::new("timesheet.toml")).unwrap();
+        assert_eq!(config.limits.max_session(), Some(Duration::hours(10)));
+        assert_eq!(config.limits.max_day(), None);
+        assert_eq!(config.limits.earliest_start, NaiveTime::from_hms_opt(6, 30, 0));
+        assert_eq!(config.limits.latest_start, None);
+
+        let error = Config::parse("[limits]\nlatest_start = \"late\"", Path::new("timesheet.toml")).unwrap_err();
+        assert!(error.to_string().contains("invalid time \"late\", expected HH:MM"));
+    }
+
+    #[test]
+    fn test_compliance_config() {
+        let config = Config::parse("[compliance]\nmax_week = 40\nmin_rest = 0\nmin_break = 45", Path::new("timesheet.toml")).unwrap();
+        assert_eq!(config.compliance.max_week(), Some(Duration::hours(40)));
+        assert_eq!(config.compliance.min_rest(), None);
+        assert_eq!(config.compliance.break_after(), Some(Duration::hours(6)));
+        assert_eq!(config.compliance.min_break(), Duration::minutes(45));
+    }
+
+    #[test]
+    fn test_unknown_keys_are_config_errors() {
+        let error = Config::parse("[invoice]\ncurrencyy = \"CAD\"", Path::new("timesheet.toml")).unwrap_err();
+        assert!(matches!(error, TimesheetError::Config { .. }));
+        assert!(error.to_string().starts_with("timesheet.toml: unknown field `currencyy`"));
+    }
+
+    #[test]
+    fn test_missing_default_config_is_empty() {
+        let directory = std::env::temp_dir().join("markdown_timesheet_no_config");
+        let config = Config::load(&directory, None).unwrap();
+        assert!(config.clients.is_empty());
+
+        let error = Config::load(&directory, Some(&directory.join("missing.toml"))).unwrap_err();
+        assert!(matches!(error, TimesheetError::Io { .. }));
+    }
+}
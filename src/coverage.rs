@@ -0,0 +1,167 @@
+use crate::config::ScheduleConfig;
+use crate::duration::DurationFormat;
+use crate::holidays::Holiday;
+use crate::DaySummary;
+use chrono::{Duration, NaiveDate, NaiveTime};
+use std::collections::HashSet;
+
+/// Something missing from the record of a scheduled day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Finding {
+    /// A past scheduled workday without a file, holiday or leave
+    MissingFile,
+    /// A past scheduled workday whose file has no entries at all
+    NoEntries,
+    /// Time between one session's stop and the next session's start
+    Gap { from: NaiveTime, to: NaiveTime, line: usize },
+}
+
+impl Finding {
+    /// The finding in words, with durations in the given format
+    pub fn describe(&self, durations: DurationFormat) -> String {
+        match self {
+            Self::MissingFile => "no file for a scheduled workday".to_string(),
+            Self::NoEntries => "file has no entries".to_string(),
+            Self::Gap { from, to, .. } => format!(
+                "unexplained gap of {} between {} and {}",
+                durations.format(*to - *from),
+                from.format("%H:%M"),
+                to.format("%H:%M")
+            ),
+        }
+    }
+
+    /// Short form for the daily summary, e.g. "2h 30m gap after 12:00"
+    pub fn short(&self, durations: DurationFormat) -> String {
+        match self {
+            Self::MissingFile => "no file".to_string(),
+            Self::NoEntries => "no entries".to_string(),
+            Self::Gap { from, to, .. } => format!("{} gap after {}", durations.format(*to - *from), from.format("%H:%M")),
+        }
+    }
+
+    /// The line the finding points at, if it has one
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::Gap { line, .. } => Some(*line),
+            Self::MissingFile | Self::NoEntries => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageIssue {
+    pub date: NaiveDate,
+    pub finding: Finding,
+}
+
+/// Gaps longer than `max_gap` between sessions that follow each other in the
+/// file. An overnight session ends the day, so nothing after it is compared.
+fn day_gaps(day: &DaySummary, max_gap: Duration) -> Vec<Finding> {
+    let mut sessions: Vec<_> = day
+        .entries
+        .iter()
+        .filter_map(|entry| Some((entry.line, entry.start_time?, entry.end_time?)))
+        .collect();
+    sessions.sort_unstable();
+
+    let mut gaps = Vec::new();
+    for pair in sessions.windows(2) {
+        let ((_, start, end), (next_line, next_start, _)) = (pair[0], pair[1]);
+        if end < start {
+            break;
+        }
+        if next_start > end && next_start - end > max_gap {
+            gaps.push(Finding::Gap { from: end, to: next_start, line: next_line });
+        }
+    }
+    gaps
+}
+
+/// Scheduled workdays from the first tracked day up to yesterday that have no
+/// file or an empty one, plus long gaps between sessions on any day. Today is
+/// still in progress, so only its gaps are reported.
+pub fn find_issues(summaries: &[DaySummary], holidays: &[Holiday], schedule: &ScheduleConfig, today: NaiveDate) -> Vec<CoverageIssue> {
+    let Some(first) = summaries.first().map(|day| day.date) else {
+        return Vec::new();
+    };
+    let holiday_dates: HashSet<_> = holidays.iter().map(|holiday| holiday.date).collect();
+    let max_gap = schedule.max_gap();
+
+    let mut issues = Vec::new();
+    let mut days = summaries.iter().peekable();
+    for date in first.iter_days().take_while(|date| *date <= today) {
+        let day = days.next_if(|day| day.date == date);
+        let scheduled = schedule.is_workday(date) && date < today && !holiday_dates.contains(&date);
+        let finding = match day {
+            None if scheduled => Some(Finding::MissingFile),
+            Some(day)
+                if scheduled
+                    && day.entries.is_empty()
+                    && day.work_time_entries.is_empty()
+                    && day.leave_duration == Duration::zero()
+                    && day.diagnostics.is_empty() =>
+            {
+                Some(Finding::NoEntries)
+            }
+            _ => None,
+        };
+        issues.extend(finding.map(|finding| CoverageIssue { date, finding }));
+
+        if let (Some(day), Some(max_gap)) = (day, max_gap) {
+            issues.extend(day_gaps(day, max_gap).into_iter().map(|finding| CoverageIssue { date, finding }));
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TimesheetParser;
+
+    fn parse(date: NaiveDate, content: &str) -> DaySummary {
+        TimesheetParser::new(false).unwrap().parse_file(content, date).unwrap()
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 9, day).unwrap()
+    }
+
+    #[test]
+    fn test_missing_and_empty_days() {
+        // Mon 1st to Mon 8th: the 2nd has no file, the 3rd only notes, the 4th is PTO,
+        // the 5th is a listed holiday and the weekend is not scheduled
+        let summaries = vec![
+            parse(date(1), "Start work 9:00\nStop work 17:00"),
+            parse(date(3), "# Notes\nForgot to track"),
+            parse(date(4), "PTO"),
+            parse(date(8), ""),
+        ];
+        let holidays = [Holiday { date: date(5), name: "Holiday".to_string() }];
+        let issues = find_issues(&summaries, &holidays, &ScheduleConfig::default(), date(8));
+        assert_eq!(issues, [
+            CoverageIssue { date: date(2), finding: Finding::MissingFile },
+            CoverageIssue { date: date(3), finding: Finding::NoEntries },
+        ]);
+    }
+
+    #[test]
+    fn test_gaps() {
+        let day = parse(date(1), "Start work 8:00\nStop work 9:00\nStart work 10:00\nStop work 12:00\nStart work 14:30\nStop work 17:00");
+        let issues = find_issues(&[day], &[], &ScheduleConfig::default(), date(1));
+        assert_eq!(issues.len(), 1);
+        let gap = &issues[0].finding;
+        assert_eq!(gap.line(), Some(5));
+        assert_eq!(gap.describe(DurationFormat::default()), "unexplained gap of 2h 30m between 12:00 and 14:30");
+        assert_eq!(gap.short(DurationFormat::default()), "2h 30m gap after 12:00");
+
+        // Sessions after midnight belong to the overnight session before them
+        let day = parse(date(1), "Start work 13:00\nStop work 1:00\nStart work 1:30\nStop work 2:00");
+        assert!(find_issues(&[day], &[], &ScheduleConfig::default(), date(1)).is_empty());
+
+        let schedule = ScheduleConfig { max_gap: 0, ..ScheduleConfig::default() };
+        let day = parse(date(1), "Start work 8:00\nStop work 9:00\nStart work 14:00\nStop work 15:00");
+        assert!(find_issues(&[day], &[], &schedule, date(1)).is_empty());
+    }
+}
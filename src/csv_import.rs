@@ -0,0 +1,243 @@
+use crate::error::TimesheetError;
+use crate::import::{split_tags, whole_minutes, ImportedSession};
+use chrono::{NaiveDate, NaiveTime};
+
+/// Date formats found in Toggl and Clockify exports; a file must fit exactly one
+const DATE_FORMATS: [&str; 4] = ["%Y-%m-%d", "%m/%d/%Y", "%d.%m.%Y", "%d/%m/%Y"];
+const TIME_FORMATS: [&str; 4] = ["%H:%M:%S", "%H:%M", "%I:%M:%S %p", "%I:%M %p"];
+
+/// Records of a CSV file with the line each record starts on. Quoted fields
+/// may contain commas, doubled quotes and line breaks.
+fn parse_records(text: &str) -> Result<Vec<(usize, Vec<String>)>, TimesheetError> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut quote_line = 1;
+
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => {
+                in_quotes = true;
+                quote_line = line;
+            }
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) if chars.peek() == Some(&'\n') => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut record)));
+                line += 1;
+                record_line = line;
+            }
+            ('\n', true) => {
+                field.push(c);
+                line += 1;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(TimesheetError::parse(quote_line, "unterminated quoted field"));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((record_line, record));
+    }
+    records.retain(|(_, record)| record.iter().any(|field| !field.trim().is_empty()));
+    Ok(records)
+}
+
+/// Positions of the columns this importer reads, found by header name
+struct Columns {
+    start_date: usize,
+    start_time: usize,
+    end_time: usize,
+    project: Option<usize>,
+    client: Option<usize>,
+    description: Option<usize>,
+    tags: Option<usize>,
+}
+
+impl Columns {
+    fn from_header(header: &[String]) -> Result<Self, TimesheetError> {
+        let find = |name: &str| header.iter().position(|column| column.trim().eq_ignore_ascii_case(name));
+        let require = |name: &str| find(name).ok_or_else(|| TimesheetError::parse(1, format!("missing column \"{name}\"")));
+        Ok(Self {
+            start_date: require("Start date")?,
+            start_time: require("Start time")?,
+            end_time: require("End time")?,
+            project: find("Project"),
+            client: find("Client"),
+            description: find("Description"),
+            tags: find("Tags"),
+        })
+    }
+}
+
+/// The one format of `DATE_FORMATS` that every `(line, date)` fits, so a
+/// day-first file is never read month-first for the days that allow it
+fn detect_date_format(dates: &[(usize, &str)]) -> Result<&'static str, TimesheetError> {
+    let fits = |format: &str| dates.iter().all(|(_, date)| NaiveDate::parse_from_str(date, format).is_ok());
+    let candidates: Vec<_> = DATE_FORMATS.into_iter().filter(|format| fits(format)).collect();
+    match (candidates.as_slice(), dates.first()) {
+        ([format], _) => Ok(format),
+        (_, None) => Ok(DATE_FORMATS[0]),
+        ([], Some(&(first_line, _))) => {
+            let unknown = dates.iter().find(|(_, date)| DATE_FORMATS.iter().all(|format| NaiveDate::parse_from_str(date, format).is_err()));
+            Err(match unknown {
+                Some((line, date)) => TimesheetError::parse(*line, format!("invalid date \"{date}\"; use --date-format to set the format")),
+                None => TimesheetError::parse(first_line, "dates are in more than one format; use --date-format"),
+            })
+        }
+        (_, Some(&(first_line, _))) => Err(TimesheetError::parse(first_line, "ambiguous date format; use --date-format")),
+    }
+}
+
+/// A trimmed field of a record, empty when the row is short
+fn field_of(record: &[String], index: usize) -> &str {
+    record.get(index).map_or("", |value| value.trim())
+}
+
+fn parse_date(text: &str, format: &str, line: usize) -> Result<NaiveDate, TimesheetError> {
+    NaiveDate::parse_from_str(text, format)
+        .map_err(|_| TimesheetError::parse(line, format!("invalid date \"{text}\" for --date-format {format}")))
+}
+
+fn parse_time(text: &str, line: usize) -> Result<NaiveTime, TimesheetError> {
+    TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(text.trim(), format).ok())
+        .ok_or_else(|| TimesheetError::parse(line, format!("invalid time \"{text}\"")))
+}
+
+/// Sessions from a Toggl Track or Clockify detailed CSV export. The project
+/// (or the client, for rows without one) becomes the first tag, followed by
+/// the row's tags; entries keep their description and lose any seconds.
+/// Without `date_format` the format is detected from all rows and must be
+/// unambiguous.
+pub fn to_sessions(text: &str, date_format: Option<&str>) -> Result<Vec<ImportedSession>, TimesheetError> {
+    let mut records = parse_records(text)?.into_iter();
+    let Some((_, header)) = records.next() else {
+        return Ok(Vec::new());
+    };
+    let columns = Columns::from_header(&header)?;
+    let records: Vec<_> = records.collect();
+    let date_format = if let Some(format) = date_format {
+        format
+    } else {
+        let dates: Vec<_> = records.iter().map(|(line, record)| (*line, field_of(record, columns.start_date))).collect();
+        detect_date_format(&dates)?
+    };
+
+    records
+        .iter()
+        .map(|(line, record)| {
+            let line = *line;
+            let field = |index: Option<usize>| index.map_or("", |index| field_of(record, index));
+            let date = parse_date(field(Some(columns.start_date)), date_format, line)?;
+            let start = whole_minutes(parse_time(field(Some(columns.start_time)), line)?);
+            let end = whole_minutes(parse_time(field(Some(columns.end_time)), line)?);
+
+            let project = Some(field(columns.project)).filter(|project| !project.is_empty());
+            let project = project.or(Some(field(columns.client)).filter(|client| !client.is_empty()));
+            let tags = project.into_iter().chain(field(columns.tags).split(',')).filter(|tag| !tag.trim().is_empty());
+            let (tags, words) = split_tags(tags);
+            let description = words
+                .into_iter()
+                .chain([field(columns.description)])
+                .filter(|text| !text.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            Ok(ImportedSession {
+                date,
+                start,
+                end: Some(end),
+                tags,
+                description: description.split_whitespace().collect::<Vec<_>>().join(" "),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOGGL: &str = "\u{feff}User,Email,Client,Project,Task,Description,Billable,Start date,Start time,End date,End time,Duration,Tags,Amount ()\r\n\
+        Jane,jane@example.com,Acme,Website,,\"Fix login, again\",Yes,2025-08-25,09:00:27,2025-08-25,12:30:45,03:30:18,\"urgent, Q3\",\r\n\
+        Jane,jane@example.com,Acme,,,\"Call with \"\"Bob\"\"\",No,2025-08-25,22:00:00,2025-08-26,01:00:00,03:00:00,,\r\n";
+
+    const CLOCKIFY: &str = "Project,Client,Description,Task,User,Group,Email,Tags,Billable,Start Date,Start Time,End Date,End Time,Duration (h),Duration (decimal)\n\
+        Internal,,\"Planning\nnext sprint\",,Jane,,jane@example.com,,No,08/26/2025,01:15:00 PM,08/26/2025,02:00:00 PM,00:45:00,0.75\n";
+
+    #[test]
+    fn test_toggl_csv() {
+        let sessions = to_sessions(TOGGL, None).unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].date.to_string(), "2025-08-25");
+        assert_eq!(sessions[0].start.to_string(), "09:00:00");
+        assert_eq!(sessions[0].end.map(|end| end.to_string()).as_deref(), Some("12:30:00"));
+        assert_eq!(sessions[0].tags, ["website", "urgent", "q3"]);
+        assert_eq!(sessions[0].description, "Fix login, again");
+        assert_eq!(sessions[1].tags, ["acme"]);
+        assert_eq!(sessions[1].description, "Call with \"Bob\"");
+        assert_eq!(sessions[1].end.map(|end| end.to_string()).as_deref(), Some("01:00:00"));
+
+        // Importing the same export again changes nothing
+        let directory = std::env::temp_dir().join(format!("markdown_timesheet_toggl_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let updates = crate::import::plan_merge(&directory, &sessions).unwrap();
+        assert_eq!(updates[0].added, 2);
+        crate::import::write_updates(&updates).unwrap();
+        assert!(crate::import::plan_merge(&directory, &to_sessions(TOGGL, None).unwrap()).unwrap().is_empty());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_clockify_csv() {
+        let sessions = to_sessions(CLOCKIFY, None).unwrap();
+        assert_eq!(sessions[0].date.to_string(), "2025-08-26");
+        assert_eq!(sessions[0].start.to_string(), "13:15:00");
+        assert_eq!(sessions[0].tags, ["internal"]);
+        assert_eq!(sessions[0].description, "Planning next sprint");
+
+        // Day-first dates are detected from the whole file, or need an explicit format when every day is 12 or less
+        let european = CLOCKIFY.replace("08/26/2025", "26/08/2025");
+        assert!(to_sessions(&european, Some("%m/%d/%Y")).is_err());
+        assert_eq!(to_sessions(&european, Some("%d/%m/%Y")).unwrap()[0].date.to_string(), "2025-08-26");
+        let row = CLOCKIFY.lines().nth(1).unwrap().to_string() + "\n" + CLOCKIFY.lines().nth(2).unwrap() + "\n";
+        let mixed = format!("{european}{}", row.replace("08/26/2025", "03/08/2025"));
+        let dates: Vec<_> = to_sessions(&mixed, None).unwrap().iter().map(|session| session.date.to_string()).collect();
+        assert_eq!(dates, ["2025-08-26", "2025-08-03"]);
+        let error = to_sessions(&CLOCKIFY.replace("08/26/2025", "03/08/2025"), None).unwrap_err();
+        assert_eq!(error.to_string(), "line 2: ambiguous date format; use --date-format");
+    }
+
+    #[test]
+    fn test_csv_errors() {
+        let error = to_sessions("Project,Start date\nAcme,2025-08-25\n", None).unwrap_err();
+        assert_eq!(error.to_string(), "line 1: missing column \"Start time\"");
+
+        let error = to_sessions("Start date,Start time,End time\n2025-08-25,9:00,10:00\n2025-25-08,9:00,10:00\n", None).unwrap_err();
+        assert_eq!(error.to_string(), "line 3: invalid date \"2025-25-08\"; use --date-format to set the format");
+        let error = to_sessions("Start date,Start time,End time\n2025-08-25,9:00,10:00\n26/08/2025,9:00,10:00\n", None).unwrap_err();
+        assert_eq!(error.to_string(), "line 2: dates are in more than one format; use --date-format");
+
+        let error = to_sessions("Start date,Start time,End time\n2025-08-25,9am,10:00\n", None).unwrap_err();
+        assert_eq!(error.to_string(), "line 2: invalid time \"9am\"");
+
+        let error = to_sessions("Start date,Start time,End time\n\"2025-08-25,9:00,10:00\n", None).unwrap_err();
+        assert!(matches!(error, TimesheetError::Parse { line: 2, .. }));
+    }
+}
@@ -0,0 +1,84 @@
+use chrono::Duration;
+use clap::ValueEnum;
+use serde::Deserialize;
+
+/// How durations are written in reports, the status bar and exports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DurationStyle {
+    /// Hours and minutes, e.g. 8h 30m
+    #[default]
+    Hm,
+    /// Decimal hours, e.g. 8.50h
+    Decimal,
+    /// Clock style, e.g. 08:30
+    #[value(name = "hh:mm")]
+    #[serde(rename = "hh:mm")]
+    Clock,
+    /// Whole minutes, e.g. 510m
+    Minutes,
+}
+
+/// The largest number of decimal places accepted for decimal hours
+pub const MAX_PRECISION: u8 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DurationFormat {
+    pub style: DurationStyle,
+    /// Decimal places for the decimal style and for exported hours
+    pub precision: u8,
+}
+
+impl Default for DurationFormat {
+    fn default() -> Self {
+        Self {
+            style: DurationStyle::Hm,
+            precision: 2,
+        }
+    }
+}
+
+impl DurationFormat {
+    pub fn format(self, duration: Duration) -> String {
+        let total_minutes = duration.num_minutes();
+        match self.style {
+            DurationStyle::Hm => format!("{}h {:02}m", total_minutes / 60, total_minutes % 60),
+            DurationStyle::Decimal => format!("{}h", self.hours(duration)),
+            DurationStyle::Clock => format!("{:02}:{:02}", total_minutes / 60, total_minutes % 60),
+            DurationStyle::Minutes => format!("{total_minutes}m"),
+        }
+    }
+
+    /// Decimal hours without a unit, e.g. "8.50", as used in exports
+    pub fn hours(self, duration: Duration) -> String {
+        #[allow(clippy::cast_precision_loss)]
+        let hours = duration.num_minutes() as f64 / 60.0;
+        format!("{hours:.precision$}", precision = usize::from(self.precision))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duration_styles() {
+        let duration = Duration::minutes(8 * 60 + 5);
+        let format = |style, precision| DurationFormat { style, precision }.format(duration);
+
+        assert_eq!(format(DurationStyle::Hm, 2), "8h 05m");
+        assert_eq!(format(DurationStyle::Decimal, 2), "8.08h");
+        assert_eq!(format(DurationStyle::Decimal, 1), "8.1h");
+        assert_eq!(format(DurationStyle::Decimal, 0), "8h");
+        assert_eq!(format(DurationStyle::Clock, 2), "08:05");
+        assert_eq!(format(DurationStyle::Minutes, 2), "485m");
+        assert_eq!(DurationFormat { style: DurationStyle::Clock, precision: 2 }.format(Duration::hours(176)), "176:00");
+    }
+
+    #[test]
+    fn test_parse_style() {
+        assert_eq!(DurationStyle::from_str("hh:mm", false), Ok(DurationStyle::Clock));
+        assert_eq!(DurationStyle::from_str("decimal", false), Ok(DurationStyle::Decimal));
+        assert!(DurationStyle::from_str("hours", false).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Every way a timesheet run can fail, grouped by cause so callers and the
+/// shell can tell them apart.
+#[derive(Debug)]
+pub enum TimesheetError {
+    /// A directory or timesheet file could not be read
+    Io { path: PathBuf, source: io::Error },
+    /// A timesheet line could not be interpreted
+    Parse {
+        path: Option<PathBuf>,
+        line: usize,
+        message: String,
+    },
+    /// An option or configuration value was missing or invalid
+    Config { setting: String, message: String },
+    /// A command cannot be carried out against the current timesheet state
+    Usage(String),
+    /// One of the built-in entry patterns failed to compile
+    Pattern(regex::Error),
+}
+
+impl TimesheetError {
+    pub fn io(path: &Path, source: io::Error) -> Self {
+        Self::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    pub fn parse(line: usize, message: impl Into<String>) -> Self {
+        Self::Parse {
+            path: None,
+            line,
+            message: message.into(),
+        }
+    }
+
+    pub fn config(setting: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::Config {
+            setting: setting.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Attach the file a parse error came from
+    #[must_use]
+    pub fn with_path(self, file: &Path) -> Self {
+        match self {
+            Self::Parse { path: None, line, message } => Self::Parse {
+                path: Some(file.to_path_buf()),
+                line,
+                message,
+            },
+            other => other,
+        }
+    }
+
+    /// Process exit code for this class of error, following the BSD `sysexits.h` conventions
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Self::Usage(_) => ExitCode::from(64),       // EX_USAGE
+            Self::Parse { .. } => ExitCode::from(65),   // EX_DATAERR
+            Self::Pattern(_) => ExitCode::from(70),     // EX_SOFTWARE
+            Self::Io { .. } => ExitCode::from(74),      // EX_IOERR
+            Self::Config { .. } => ExitCode::from(78),  // EX_CONFIG
+        }
+    }
+}
+
+impl fmt::Display for TimesheetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            Self::Parse {
+                path: Some(path),
+                line,
+                message,
+            } => write!(f, "{}:{line}: {message}", path.display()),
+            Self::Parse { path: None, line, message } => write!(f, "line {line}: {message}"),
+            Self::Config { setting, message } => write!(f, "{setting}: {message}"),
+            Self::Usage(message) => write!(f, "{message}"),
+            Self::Pattern(error) => write!(f, "invalid entry pattern: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for TimesheetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Pattern(error) => Some(error),
+            Self::Parse { .. } | Self::Config { .. } | Self::Usage(_) => None,
+        }
+    }
+}
+
+impl From<regex::Error> for TimesheetError {
+    fn from(error: regex::Error) -> Self {
+        Self::Pattern(error)
+    }
+}
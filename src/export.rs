@@ -0,0 +1,98 @@
+use crate::duration::DurationFormat;
+use crate::DaySummary;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::fmt::Write as _;
+
+/// One exported day, flattened for spreadsheets and scripts
+#[derive(Debug, Serialize)]
+struct DayRecord {
+    date: NaiveDate,
+    weekday: String,
+    minutes: i64,
+    /// Decimal hours rounded to the configured precision
+    hours: f64,
+    /// The total in the configured duration style
+    duration: String,
+    tentative: bool,
+    incomplete: bool,
+}
+
+impl DayRecord {
+    fn new(day: &DaySummary, format: DurationFormat) -> Self {
+        Self {
+            date: day.date,
+            weekday: day.date.format("%a").to_string(),
+            minutes: day.total_duration.num_minutes(),
+            hours: format.hours(day.total_duration).parse().unwrap_or_default(),
+            duration: format.format(day.total_duration),
+            tentative: day.has_tentative,
+            incomplete: day.has_incomplete,
+        }
+    }
+}
+
+pub fn to_csv(summaries: &[DaySummary], format: DurationFormat) -> String {
+    let mut csv = String::from("date,weekday,minutes,hours,duration,tentative,incomplete\n");
+    for day in summaries {
+        let record = DayRecord::new(day, format);
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{},{},{}",
+            record.date,
+            record.weekday,
+            record.minutes,
+            format.hours(day.total_duration),
+            record.duration,
+            record.tentative,
+            record.incomplete
+        );
+    }
+    csv
+}
+
+pub fn to_json(summaries: &[DaySummary], format: DurationFormat) -> String {
+    let records: Vec<_> = summaries.iter().map(|day| DayRecord::new(day, format)).collect();
+    serde_json::to_string_pretty(&records).expect("day records always serialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationStyle;
+    use chrono::Duration;
+
+    fn day(date: &str, minutes: i64) -> DaySummary {
+        DaySummary {
+            date: date.parse().unwrap(),
+            total_duration: Duration::minutes(minutes),
+            has_incomplete: minutes == 0,
+            ..DaySummary::default()
+        }
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let csv = to_csv(&[day("2025-08-25", 450), day("2025-08-26", 0)], DurationFormat::default());
+        assert_eq!(
+            csv,
+            "date,weekday,minutes,hours,duration,tentative,incomplete\n\
+             2025-08-25,Mon,450,7.50,7h 30m,false,false\n\
+             2025-08-26,Tue,0,0.00,0h 00m,false,true\n"
+        );
+
+        let format = DurationFormat { style: DurationStyle::Clock, precision: 1 };
+        let csv = to_csv(&[day("2025-08-25", 445)], format);
+        assert!(csv.ends_with("2025-08-25,Mon,445,7.4,07:25,false,false\n"));
+    }
+
+    #[test]
+    fn test_to_json() {
+        let json = to_json(&[day("2025-08-25", 445)], DurationFormat::default());
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(json[0]["date"], "2025-08-25");
+        assert_eq!(json[0]["minutes"], 445);
+        assert_eq!(json[0]["hours"], 7.42);
+        assert_eq!(json[0]["duration"], "7h 25m");
+    }
+}
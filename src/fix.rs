@@ -0,0 +1,414 @@
+use crate::cli::FixArgs;
+use crate::clock::Clock;
+use crate::config::FmtConfig;
+use crate::diff::unified_diff;
+use crate::error::TimesheetError;
+use crate::fmt::EntryFormatter;
+use crate::TimesheetParser;
+use chrono::{Local, NaiveDate, NaiveTime, Timelike};
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// A change `fix` makes to one line of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repair {
+    /// Line in the original file
+    pub line: usize,
+    pub description: String,
+}
+
+/// A start or stop entry and the line it is on
+struct ClockEntry {
+    index: usize,
+    clock: Clock,
+    time: NaiveTime,
+}
+
+/// The repaired content of one file, what was changed, and the problems that
+/// need more information than the file has
+#[derive(Debug, Default)]
+pub struct FilePlan {
+    pub content: String,
+    pub repairs: Vec<Repair>,
+    pub unresolved: Vec<Repair>,
+}
+
+pub struct Fixer<'a> {
+    formatter: EntryFormatter<'a>,
+    meridiem_regex: Regex,
+}
+
+impl<'a> Fixer<'a> {
+    pub fn new(style: &'a FmtConfig) -> Result<Self, TimesheetError> {
+        Ok(Self {
+            formatter: EntryFormatter::new(style)?,
+            meridiem_regex: Regex::new(r"(?i)^\s*([ap])\.?m\b\.?")?,
+        })
+    }
+
+    /// The start or stop entry on a line, with the byte range its canonical
+    /// form replaces. "5:30pm" becomes "17:30"; impossible times are left alone.
+    fn canonical_entry(&self, line: &str) -> Option<(Clock, NaiveTime, std::ops::Range<usize>)> {
+        let (clock, time, range) = self.formatter.clock_entry(line)?;
+        let Some(meridiem) = self.meridiem_regex.captures(&line[range.end..]) else {
+            return Some((clock, time, range));
+        };
+        if !(1..=12).contains(&time.hour()) {
+            return None;
+        }
+        let hour = time.hour() % 12 + if meridiem[1].eq_ignore_ascii_case("p") { 12 } else { 0 };
+        Some((clock, time.with_hour(hour)?, range.start..range.end + meridiem.get(0)?.end()))
+    }
+
+    /// Work out the repairs for one daily file. `close` is the stop time for
+    /// one open session, picked by the line of its start when the day has
+    /// more than one; it must be after the start, as overnight closes are not
+    /// supported. Today's last open session is still running and is left
+    /// alone. Line endings, and a missing final newline, are kept.
+    pub fn plan(&self, content: &str, date: NaiveDate, today: NaiveDate, close: Option<(NaiveTime, Option<usize>)>) -> FilePlan {
+        let pieces: Vec<_> = content.split_inclusive('\n').map(|piece| piece.split_at(piece.trim_end_matches(['\n', '\r']).len())).collect();
+        let mut lines: Vec<String> = pieces.iter().map(|(line, _)| (*line).to_string()).collect();
+        let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+        let mut plan = FilePlan::default();
+
+        let mut entries = Vec::new();
+        let mut rewrites = Vec::new();
+        for (index, line) in lines.iter_mut().enumerate() {
+            let Some((clock, time, range)) = self.canonical_entry(line) else {
+                continue;
+            };
+            let canonical = self.formatter.entry_line(clock, time);
+            if line[range.clone()] != canonical {
+                rewrites.push(Repair {
+                    line: index + 1,
+                    description: format!("rewrite \"{}\" as \"{canonical}\"", &line[range.clone()]),
+                });
+                line.replace_range(range, &canonical);
+            }
+            entries.push(ClockEntry { index, clock, time });
+        }
+
+        // Pair starts with stops the way the parser does
+        let mut open: Option<&ClockEntry> = None;
+        let mut unterminated = Vec::new();
+        let mut sessions: Vec<(NaiveTime, NaiveTime)> = Vec::new();
+        let mut removed = BTreeSet::new();
+        for entry in &entries {
+            match (entry.clock, open) {
+                (Clock::Start, previous) => {
+                    unterminated.extend(previous);
+                    open = Some(entry);
+                }
+                (Clock::Stop, Some(start)) => {
+                    let times = (start.time, entry.time);
+                    if sessions.contains(&times) {
+                        removed.extend([start.index, entry.index]);
+                        plan.repairs.push(Repair {
+                            line: start.index + 1,
+                            description: format!(
+                                "remove duplicate session {}–{} (lines {} and {})",
+                                times.0.format("%H:%M"),
+                                times.1.format("%H:%M"),
+                                start.index + 1,
+                                entry.index + 1
+                            ),
+                        });
+                    } else {
+                        sessions.push(times);
+                    }
+                    open = None;
+                }
+                (Clock::Stop, None) => plan.unresolved.push(Repair {
+                    line: entry.index + 1,
+                    description: format!("stop at {} has no matching start", entry.time.format("%H:%M")),
+                }),
+            }
+        }
+        if date < today {
+            unterminated.extend(open);
+        }
+
+        // Rewriting a line that is removed anyway is not worth mentioning
+        plan.repairs.extend(rewrites.into_iter().filter(|repair| !removed.contains(&(repair.line - 1))));
+
+        let mut inserted = self.close_sessions(&mut plan, &lines, &unterminated, date, close);
+
+        let mut fixed: Vec<(String, &str)> = Vec::with_capacity(lines.len() + inserted.len());
+        for (index, (line, (_, ending))) in lines.into_iter().zip(pieces).enumerate() {
+            fixed.extend(inserted.remove(&index).map(|stop| (stop, newline)));
+            if !removed.contains(&index) {
+                fixed.push((line, ending));
+            }
+        }
+        // Stops after the last line end the file the way it ended before
+        if !inserted.is_empty() {
+            let last_ending = fixed.last_mut().map_or(newline, |(_, ending)| std::mem::replace(ending, newline));
+            fixed.extend(inserted.into_values().map(|stop| (stop, newline)));
+            if let Some((_, ending)) = fixed.last_mut() {
+                *ending = last_ending;
+            }
+        }
+
+        plan.content = fixed.into_iter().flat_map(|(line, ending)| [line, ending.to_string()]).collect();
+        plan.repairs.sort_by_key(|repair| repair.line);
+        plan.unresolved.sort_by_key(|repair| repair.line);
+        plan
+    }
+
+    /// Stops for the open session picked by `close`, keyed by the line they go
+    /// before; the sessions that stay open are added to `plan.unresolved`
+    fn close_sessions(
+        &self,
+        plan: &mut FilePlan,
+        lines: &[String],
+        unterminated: &[&ClockEntry],
+        date: NaiveDate,
+        close: Option<(NaiveTime, Option<usize>)>,
+    ) -> BTreeMap<usize, String> {
+        let picked = close.and_then(|(close_at, line)| {
+            let position = match line {
+                Some(line) => unterminated.iter().position(|start| start.index + 1 == line),
+                None => (unterminated.len() == 1).then_some(0),
+            };
+            if position.is_none() {
+                if let Some(line) = line {
+                    plan.unresolved.push(Repair { line, description: "--line is not the start of an open session".to_string() });
+                }
+            }
+            position.map(|position| (position, close_at))
+        });
+
+        let mut inserted: BTreeMap<usize, String> = BTreeMap::new();
+        for (position, start) in unterminated.iter().enumerate() {
+            let started = start.time.format("%H:%M");
+            let close_at = match picked {
+                Some((picked, close_at)) if picked == position && start.time < close_at => close_at,
+                Some((picked, close_at)) if picked == position => {
+                    let reason = if close_at == start.time {
+                        "the time it started"
+                    } else {
+                        "before its start; closing a session overnight is not supported, so add its stop by hand"
+                    };
+                    plan.unresolved.push(Repair {
+                        line: start.index + 1,
+                        description: format!("session started at {started} cannot be closed at {}, {reason}", close_at.format("%H:%M")),
+                    });
+                    continue;
+                }
+                _ => {
+                    plan.unresolved.push(Repair {
+                        line: start.index + 1,
+                        description: format!("session started at {started} has no stop; use --date {date} --line {} --close-at HH:MM to close it", start.index + 1),
+                    });
+                    continue;
+                }
+            };
+            // Before the next entry, so the notes under the start stay with the session
+            let position = (start.index + 1..lines.len()).find(|&index| self.formatter.is_entry(&lines[index])).unwrap_or(lines.len());
+            let stop = self.formatter.entry_line(Clock::Stop, close_at);
+            plan.repairs.push(Repair {
+                line: start.index + 1,
+                description: format!("close session started at {started} with \"{stop}\""),
+            });
+            inserted.insert(position, stop);
+        }
+        inserted
+    }
+}
+
+/// Save `original` next to `path` as `YYYY-MM-DD.md.bak`, or `.md.bak.1`,
+/// `.md.bak.2` and so on when earlier backups exist, which are never overwritten
+fn write_backup(path: &Path, original: &str) -> Result<PathBuf, TimesheetError> {
+    for number in 0_u32.. {
+        let backup = if number == 0 { path.with_extension("md.bak") } else { path.with_extension(format!("md.bak.{number}")) };
+        match OpenOptions::new().write(true).create_new(true).open(&backup) {
+            Ok(mut file) => {
+                file.write_all(original.as_bytes()).map_err(|error| TimesheetError::io(&backup, error))?;
+                return Ok(backup);
+            }
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => {}
+            Err(error) => return Err(TimesheetError::io(&backup, error)),
+        }
+    }
+    unreachable!("a free backup name exists")
+}
+
+/// Write each fixed file after saving its original content next to it; returns the backups
+fn write_fixes(fixes: &[(PathBuf, String, FilePlan)]) -> Result<Vec<PathBuf>, TimesheetError> {
+    let mut backups = Vec::new();
+    for (path, original, plan) in fixes {
+        backups.push(write_backup(path, original)?);
+        fs::write(path, &plan.content).map_err(|error| TimesheetError::io(path, error))?;
+    }
+    Ok(backups)
+}
+
+fn confirm(question: &str) -> Result<bool, TimesheetError> {
+    print!("{question} [y/N] ");
+    let stdin_error = |error| TimesheetError::io(Path::new("<stdin>"), error);
+    io::stdout().flush().map_err(stdin_error)?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer).map_err(stdin_error)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Show the repairs for every file in the directory as unified diffs, then
+/// write them after confirmation (or straight away with `--apply`). Entries
+/// are rewritten in the `[fmt]` form; unreadable files are skipped unless `--strict`.
+pub fn run(args: &FixArgs, style: &FmtConfig) -> Result<(), TimesheetError> {
+    let fixer = Fixer::new(style)?;
+    let today = Local::now().date_naive();
+    let mut changed = Vec::new();
+    let mut unresolved = Vec::new();
+    let mut skipped = Vec::new();
+
+    let files = TimesheetParser::collect_timesheet_files(&args.common.directory)?;
+    if let Some(date) = args.date.filter(|date| !files.iter().any(|(file_date, _)| file_date == date)) {
+        return Err(TimesheetError::Usage(format!("no timesheet file for --date {date}")));
+    }
+    for (date, path) in files {
+        let original = match fs::read_to_string(&path) {
+            Ok(original) => original,
+            Err(error) if args.common.strict => return Err(TimesheetError::io(&path, error)),
+            Err(error) => {
+                skipped.push(TimesheetError::io(&path, error).to_string());
+                continue;
+            }
+        };
+        let close = args.close_at.filter(|_| args.date == Some(date)).map(|close_at| (close_at, args.line));
+        let plan = fixer.plan(&original, date, today, close);
+        unresolved.extend(plan.unresolved.iter().map(|repair| format!("{}:{}: {}", path.display(), repair.line, repair.description)));
+        if !plan.repairs.is_empty() {
+            changed.push((path, original, plan));
+        }
+    }
+
+    for (path, original, plan) in &changed {
+        for repair in &plan.repairs {
+            println!("{}:{}: {}", path.display(), repair.line, repair.description);
+        }
+        let label = path.display().to_string();
+        print!("{}", unified_diff(&label, &label, original, &plan.content));
+    }
+    if !unresolved.is_empty() {
+        println!("\nNot fixed:");
+        for problem in &unresolved {
+            println!("{problem}");
+        }
+    }
+    print!("{}", crate::report::render_warnings(&skipped));
+
+    let repair_count: usize = changed.iter().map(|(_, _, plan)| plan.repairs.len()).sum();
+    if changed.is_empty() {
+        println!("Nothing to fix");
+        return Ok(());
+    }
+    let repair_noun = if repair_count == 1 { "repair" } else { "repairs" };
+    let file_noun = if changed.len() == 1 { "file" } else { "files" };
+    if !args.apply && !confirm(&format!("\nApply {repair_count} {repair_noun} to {} {file_noun}?", changed.len()))? {
+        println!("No files changed");
+        return Ok(());
+    }
+
+    for backup in write_fixes(&changed)? {
+        println!("Original saved as {}", backup.display());
+    }
+    println!("Fixed {} {file_noun}", changed.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 9, day).unwrap()
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn plan(content: &str, close: Option<(NaiveTime, Option<usize>)>) -> FilePlan {
+        Fixer::new(&FmtConfig::default()).unwrap().plan(content, date(1), date(2), close)
+    }
+
+    #[test]
+    fn test_rewrites_entries() {
+        let fixed = plan("# Monday\n- Started working at 8:30 #acme\nlunch at noon\nstop work 12:00pm\nStart work 1:15 PM\nStopped working at 5:30p.m. done\n", None);
+        assert_eq!(fixed.content, "# Monday\n- Start work 8:30 #acme\nlunch at noon\nStop work 12:00\nStart work 13:15\nStop work 17:30 done\n");
+        assert_eq!(fixed.repairs.len(), 4);
+        assert_eq!(fixed.repairs[3].description, "rewrite \"Stopped working at 5:30p.m.\" as \"Stop work 17:30\"");
+
+        // Canonical files and impossible times are left as they are
+        assert!(plan("Start work 9:00\nStop work 17:00", None).repairs.is_empty());
+        assert!(plan("Start work 13:00pm\nStop work 25:00", None).repairs.is_empty());
+        assert_eq!(plan("Start work 12:30am\nStop work 1:00 amended", None).content, "Start work 0:30\nStop work 1:00 amended");
+    }
+
+    #[test]
+    fn test_removes_duplicate_sessions() {
+        let fixed = plan("Start work 9:00\nStop work 12:00\nStart work 9:00\nStop work 12:00\nStart work 13:00\nStop work 17:00\n", None);
+        assert_eq!(fixed.content, "Start work 9:00\nStop work 12:00\nStart work 13:00\nStop work 17:00\n");
+        assert_eq!(fixed.repairs[0].description, "remove duplicate session 09:00–12:00 (lines 3 and 4)");
+    }
+
+    #[test]
+    fn test_closes_open_sessions() {
+        let content = "Start work 9:00\nNotes on the morning\n\nStart work 13:00\n";
+        let fixed = plan(content, Some((time(12, 0), Some(1))));
+        assert_eq!(fixed.content, "Start work 9:00\nNotes on the morning\n\nStop work 12:00\nStart work 13:00\n");
+        assert_eq!(fixed.unresolved.len(), 1);
+
+        // A stop must come after the start, and two open sessions need --line
+        let fixed = plan(content, Some((time(12, 0), Some(4))));
+        assert_eq!(fixed.content, content);
+        assert_eq!(
+            fixed.unresolved[1].description,
+            "session started at 13:00 cannot be closed at 12:00, before its start; closing a session overnight is not supported, so add its stop by hand"
+        );
+        let fixed = plan(content, Some((time(13, 0), Some(4))));
+        assert_eq!(fixed.unresolved[1].description, "session started at 13:00 cannot be closed at 13:00, the time it started");
+        let fixed = plan(content, Some((time(18, 0), None)));
+        assert_eq!(fixed.content, content);
+        assert_eq!(fixed.unresolved.len(), 2);
+        assert_eq!(fixed.unresolved[0].description, "session started at 09:00 has no stop; use --date 2025-09-01 --line 1 --close-at HH:MM to close it");
+        assert_eq!(plan("Start work 13:00", Some((time(18, 0), None))).content, "Start work 13:00\nStop work 18:00");
+
+        // Today's last session is still running
+        let fixed = Fixer::new(&FmtConfig::default()).unwrap().plan("Start work 9:00", date(2), date(2), Some((time(17, 0), None)));
+        assert!(fixed.repairs.is_empty() && fixed.unresolved.is_empty());
+    }
+
+    #[test]
+    fn test_keeps_line_endings() {
+        let fixed = plan("start work 9:00\r\nNotes\r\nStart work 13:00\r\nStop work 17:00", Some((time(12, 0), None)));
+        assert_eq!(fixed.content, "Start work 9:00\r\nNotes\r\nStop work 12:00\r\nStart work 13:00\r\nStop work 17:00");
+        let fixed = plan("Start work 9:00\r\nNotes\r\n", Some((time(12, 0), None)));
+        assert_eq!(fixed.content, "Start work 9:00\r\nNotes\r\nStop work 12:00\r\n");
+        assert_eq!(plan("Start work 9:00\nStop work 12:00\nstart work 9:00\nStop work 12:00", None).content, "Start work 9:00\nStop work 12:00\n");
+    }
+
+    #[test]
+    fn test_write_keeps_backup() {
+        let directory = std::env::temp_dir().join(format!("markdown_timesheet_fix_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("2025-09-01.md");
+        let original = "start work 9:00\nStop work 12:00\n".to_string();
+        let fixed = plan(&original, None);
+        let backups = write_fixes(&[(path.clone(), original.clone(), fixed)]).unwrap();
+        assert_eq!(backups, [directory.join("2025-09-01.md.bak")]);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "Start work 9:00\nStop work 12:00\n");
+        assert_eq!(fs::read_to_string(&backups[0]).unwrap(), original);
+
+        // A second run keeps the first backup
+        let current = fs::read_to_string(&path).unwrap();
+        let backups = write_fixes(&[(path.clone(), current.clone(), plan(&current, None))]).unwrap();
+        assert_eq!(backups, [directory.join("2025-09-01.md.bak.1")]);
+        assert_eq!(fs::read_to_string(directory.join("2025-09-01.md.bak")).unwrap(), original);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,209 @@
+use crate::cli::FmtArgs;
+use crate::clock::Clock;
+use crate::config::FmtConfig;
+use crate::diff::unified_diff;
+use crate::error::TimesheetError;
+use crate::TimesheetParser;
+use chrono::NaiveTime;
+use std::fs;
+use std::process::ExitCode;
+
+/// Rewrites the entries the parser recognises into the configured form,
+/// leaving every other character of the file as it is
+pub struct EntryFormatter<'a> {
+    parser: TimesheetParser,
+    style: &'a FmtConfig,
+}
+
+impl<'a> EntryFormatter<'a> {
+    /// A formatter for `style`, which has to produce entries the parser reads
+    /// back the same way so that formatting twice changes nothing
+    pub fn new(style: &'a FmtConfig) -> Result<Self, TimesheetError> {
+        let formatter = Self {
+            parser: TimesheetParser::new(false)?,
+            style,
+        };
+        let time = NaiveTime::from_hms_opt(9, 0, 0).unwrap_or_default();
+        for (clock, setting) in [(Clock::Start, "fmt.start"), (Clock::Stop, "fmt.stop")] {
+            let line = formatter.entry_line(clock, time);
+            if formatter.clock_entry(&line).map(|(found, time, range)| (found, time, range.len())) != Some((clock, time, line.len())) {
+                return Err(TimesheetError::config(setting, format!("\"{line}\" is not read back as a {} entry", clock_name(clock))));
+            }
+        }
+        Ok(formatter)
+    }
+
+    /// The canonical text for a start or stop at `time`
+    pub fn entry_line(&self, clock: Clock, time: NaiveTime) -> String {
+        let prefix = match clock {
+            Clock::Start => &self.style.start,
+            Clock::Stop => &self.style.stop,
+        };
+        let format = if self.style.zero_pad { "%H:%M" } else { "%-H:%M" };
+        format!("{prefix} {}", time.format(format))
+    }
+
+    /// The start or stop entry the parser finds on a line, with its time and
+    /// the byte range of the match. Impossible times such as 25:00 give `None`.
+    pub fn clock_entry(&self, line: &str) -> Option<(Clock, NaiveTime, std::ops::Range<usize>)> {
+        let (clock, caps) = match self.parser.start_regex.captures(line) {
+            Some(caps) => (Clock::Start, caps),
+            None => (Clock::Stop, self.parser.stop_regex.captures(line)?),
+        };
+        let time = NaiveTime::from_hms_opt(caps[1].parse().ok()?, caps[2].parse().ok()?, 0)?;
+        Some((clock, time, caps.get(0)?.range()))
+    }
+
+    /// Whether the parser treats the line as an entry of any kind
+    pub fn is_entry(&self, line: &str) -> bool {
+        self.parser.is_entry(line)
+    }
+
+    /// One line with its entry rewritten, or unchanged when it has none
+    fn format_line(&self, line: &str) -> String {
+        let parser = &self.parser;
+        let replacement = if parser.start_regex.is_match(line) || parser.stop_regex.is_match(line) {
+            self.clock_entry(line).map(|(clock, time, range)| (self.entry_line(clock, time), range))
+        } else {
+            self.parser.work_time_regex.captures(line).and_then(|caps| {
+                let amount: u32 = caps[1].parse().ok()?;
+                let unit = if caps[2].to_lowercase().starts_with("hour") { "hour" } else { "minute" };
+                let plural = if amount == 1 { "" } else { "s" };
+                Some((format!("Work time {amount} {unit}{plural}"), caps.get(0)?.range()))
+            })
+        };
+
+        match replacement {
+            Some((entry, range)) => format!("{}{entry}{}", &line[..range.start], &line[range.end..]),
+            None => line.to_string(),
+        }
+    }
+
+    /// The whole file with every entry in canonical form and line endings kept
+    pub fn format(&self, content: &str) -> String {
+        let mut out = String::with_capacity(content.len());
+        for piece in content.split_inclusive('\n') {
+            let line = piece.trim_end_matches(['\n', '\r']);
+            out.push_str(&self.format_line(line));
+            out.push_str(&piece[line.len()..]);
+        }
+        out
+    }
+}
+
+fn clock_name(clock: Clock) -> &'static str {
+    match clock {
+        Clock::Start => "start",
+        Clock::Stop => "stop",
+    }
+}
+
+/// Format every daily file in place, or with `--check` show what would change
+/// and fail if anything would. Unreadable files are skipped unless `--strict`,
+/// in which case nothing is written.
+pub fn run(args: &FmtArgs, style: &FmtConfig) -> Result<ExitCode, TimesheetError> {
+    let formatter = EntryFormatter::new(style)?;
+    let mut rewrites = Vec::new();
+    let mut skipped = Vec::new();
+
+    for (_, path) in TimesheetParser::collect_timesheet_files(&args.common.directory)? {
+        let original = match fs::read_to_string(&path) {
+            Ok(original) => original,
+            Err(error) if args.common.strict => return Err(TimesheetError::io(&path, error)),
+            Err(error) => {
+                skipped.push(TimesheetError::io(&path, error).to_string());
+                continue;
+            }
+        };
+        let rewritten = formatter.format(&original);
+        if rewritten != original {
+            rewrites.push((path, original, rewritten));
+        }
+    }
+
+    for (path, original, rewritten) in &rewrites {
+        if args.check {
+            let label = path.display().to_string();
+            print!("{}", unified_diff(&label, &label, original, rewritten));
+        } else {
+            fs::write(path, rewritten).map_err(|error| TimesheetError::io(path, error))?;
+        }
+    }
+    print!("{}", crate::report::render_warnings(&skipped));
+
+    let changed = rewrites.len();
+    let noun = if changed == 1 { "file" } else { "files" };
+    match (args.check, changed) {
+        (_, 0) => println!("All files are formatted"),
+        (true, _) => {
+            println!("{changed} {noun} would be reformatted");
+            return Ok(ExitCode::FAILURE);
+        }
+        (false, _) => println!("Reformatted {changed} {noun}"),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Command};
+    use clap::Parser;
+
+    const MESSY: &str = "# Monday\r\n- started working at 08:30 #acme on the release\r\nStopped Working 12:00 lunch\r\n\
+                         Start work at 25:00\r\nwork time 1 hours review\r\nWORK TIME 45 minute calls\r\nStat holiday\r\n";
+
+    #[test]
+    fn test_format_entries_keeps_prose() {
+        let style = FmtConfig::default();
+        let formatter = EntryFormatter::new(&style).unwrap();
+        let formatted = formatter.format(MESSY);
+        assert_eq!(
+            formatted,
+            "# Monday\r\n- Start work 8:30 #acme on the release\r\nStop work 12:00 lunch\r\n\
+             Start work at 25:00\r\nWork time 1 hour review\r\nWork time 45 minutes calls\r\nStat holiday\r\n"
+        );
+        assert_eq!(formatter.format(&formatted), formatted);
+        assert_eq!(formatter.format("Start work 9:00"), "Start work 9:00");
+    }
+
+    #[test]
+    fn test_configured_style() {
+        let style = FmtConfig {
+            start: "Started working at".to_string(),
+            stop: "Stopped working at".to_string(),
+            zero_pad: true,
+        };
+        let formatter = EntryFormatter::new(&style).unwrap();
+        let formatted = formatter.format(MESSY);
+        assert!(formatted.contains("- Started working at 08:30 #acme on the release\r\nStopped working at 12:00 lunch\r\n"));
+        assert_eq!(formatter.format(&formatted), formatted);
+
+        // Entries the parser would read differently can't be canonical
+        for (start, stop) in [("Begin", "Stop work"), ("Start work", "Stop work, start work")] {
+            let style = FmtConfig { start: start.to_string(), stop: stop.to_string(), zero_pad: false };
+            assert!(matches!(EntryFormatter::new(&style), Err(TimesheetError::Config { .. })));
+        }
+    }
+
+    #[test]
+    fn test_run_skips_unreadable_files() {
+        let directory = std::env::temp_dir().join(format!("markdown_timesheet_fmt_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("2025-09-01.md"), "start work 9:00\n").unwrap();
+        fs::write(directory.join("2025-09-02.md"), b"\xff\xfe").unwrap();
+        let args = |extra: &[&str]| {
+            let argv = ["markdown_timesheet", "fmt", directory.to_str().unwrap()].into_iter().chain(extra.iter().copied());
+            match Cli::parse_from(argv).into_command() {
+                Command::Fmt(args) => args,
+                command => panic!("unexpected command {command:?}"),
+            }
+        };
+
+        assert!(matches!(run(&args(&["--strict"]), &FmtConfig::default()), Err(TimesheetError::Io { .. })));
+        assert_eq!(fs::read_to_string(directory.join("2025-09-01.md")).unwrap(), "start work 9:00\n");
+        assert_eq!(run(&args(&[]), &FmtConfig::default()).unwrap(), ExitCode::SUCCESS);
+        assert_eq!(fs::read_to_string(directory.join("2025-09-01.md")).unwrap(), "Start work 9:00\n");
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,227 @@
+use crate::config::{HolidayConfig, ScheduleConfig};
+use crate::error::TimesheetError;
+use crate::DaySummary;
+use chrono::{Duration, NaiveDate};
+use std::fs;
+use std::path::Path;
+
+/// A public holiday read from the configured holiday file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub name: String,
+}
+
+/// Content lines of an iCalendar file with folded lines joined, numbered by
+/// the physical line they start on
+fn unfold(text: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some((_, last))) => last.push_str(continuation),
+            _ => lines.push((index + 1, line.to_string())),
+        }
+    }
+    lines
+}
+
+fn unescape_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push(' '),
+            Some(escaped) => out.push(escaped),
+            None => {}
+        }
+    }
+    out
+}
+
+/// The date of a DTSTART or DTEND value, whether it is a date or a date-time
+fn ics_date(value: &str, line: usize) -> Result<NaiveDate, TimesheetError> {
+    value
+        .get(..8)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        .ok_or_else(|| TimesheetError::parse(line, format!("invalid date \"{value}\"")))
+}
+
+/// Holidays in an iCalendar file, one per day of each event. Events spanning
+/// several days (an exclusive DTEND) give one holiday per day; recurrence
+/// rules are not expanded, so the file has to list every year's dates.
+pub fn parse_ics(text: &str) -> Result<Vec<Holiday>, TimesheetError> {
+    let mut holidays = Vec::new();
+    let mut event: Option<(Option<NaiveDate>, Option<NaiveDate>, String)> = None;
+
+    for (line, content) in unfold(text) {
+        let Some((name_and_params, value)) = content.split_once(':') else {
+            continue;
+        };
+        let name = name_and_params.split(';').next().unwrap_or_default().to_ascii_uppercase();
+        match (name.as_str(), &mut event) {
+            ("BEGIN", _) if value.eq_ignore_ascii_case("VEVENT") => event = Some((None, None, String::new())),
+            ("DTSTART", Some((start, _, _))) => *start = Some(ics_date(value, line)?),
+            ("DTEND", Some((_, end, _))) => *end = Some(ics_date(value, line)?),
+            ("SUMMARY", Some((_, _, summary))) => *summary = unescape_text(value),
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                let Some((Some(start), end, summary)) = event.take() else {
+                    return Err(TimesheetError::parse(line, "event has no DTSTART"));
+                };
+                let end = end.filter(|end| *end > start).unwrap_or(start + Duration::days(1));
+                let name = if summary.is_empty() { "Holiday".to_string() } else { summary };
+                holidays.extend(start.iter_days().take_while(|date| *date < end).map(|date| Holiday {
+                    date,
+                    name: name.clone(),
+                }));
+            }
+            _ => {}
+        }
+    }
+    Ok(holidays)
+}
+
+/// Holidays in a plain list with one "YYYY-MM-DD Name" per line. Blank lines
+/// and lines starting with `#` are ignored.
+pub fn parse_list(text: &str) -> Result<Vec<Holiday>, TimesheetError> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_num, line)| {
+            let (date, name) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| TimesheetError::parse(line_num, format!("invalid date \"{date}\"; expected YYYY-MM-DD")))?;
+            let name = name.trim();
+            Ok(Holiday {
+                date,
+                name: if name.is_empty() { "Holiday".to_string() } else { name.to_string() },
+            })
+        })
+        .collect()
+}
+
+/// The holidays from the configured file, resolved against the timesheet
+/// directory. Files ending in `.ics` are read as iCalendar, anything else as
+/// a holiday list. No file configured means no holidays.
+pub fn load(directory: &Path, config: &HolidayConfig) -> Result<Vec<Holiday>, TimesheetError> {
+    let Some(file) = &config.file else {
+        return Ok(Vec::new());
+    };
+    let path = directory.join(file);
+    let text = fs::read_to_string(&path).map_err(|error| TimesheetError::io(&path, error))?;
+    let holidays = if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("ics")) {
+        parse_ics(&text)
+    } else {
+        parse_list(&text)
+    };
+    holidays.map_err(|error| error.with_path(&path))
+}
+
+/// Credit `hours` of leave on every holiday that falls on a scheduled workday,
+/// from the first tracked day through `today`, adding a day for holidays
+/// without a file. Days that already record a holiday or PTO entry keep
+/// their own credit, so a holiday written into the day's notes is not
+/// counted twice.
+pub fn apply(summaries: &mut Vec<DaySummary>, holidays: &[Holiday], schedule: &ScheduleConfig, hours: Duration, today: NaiveDate) {
+    let Some(tracking_start) = summaries.first().map(|day| day.date) else {
+        return;
+    };
+
+    for holiday in holidays {
+        if holiday.date < tracking_start || holiday.date > today || !schedule.is_workday(holiday.date) {
+            continue;
+        }
+        let index = match summaries.binary_search_by_key(&holiday.date, |day| day.date) {
+            Ok(index) => index,
+            Err(index) => {
+                summaries.insert(index, DaySummary { date: holiday.date, ..DaySummary::default() });
+                index
+            }
+        };
+
+        let day = &mut summaries[index];
+        if day.holiday.is_some() {
+            continue;
+        }
+        if day.leave_duration == Duration::zero() {
+            day.total_duration += hours;
+            day.leave_duration += hours;
+        }
+        day.holiday = Some(holiday.name.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, Weekday};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 9, day).unwrap()
+    }
+
+    #[test]
+    fn test_parse_ics() {
+        let ics = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250901\r\nDTEND;VALUE=DATE:20250902\r\n\
+                   SUMMARY:Labour\r\n  Day\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nDTSTART:20251224T000000\r\n\
+                   DTEND:20251227T000000\r\nSUMMARY:Christmas\\, Boxing Day\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let holidays = parse_ics(ics).unwrap();
+        assert_eq!(holidays.len(), 4);
+        assert_eq!(holidays[0], Holiday { date: date(1), name: "Labour Day".to_string() });
+        assert_eq!(holidays[3].date, NaiveDate::from_ymd_opt(2025, 12, 26).unwrap());
+        assert_eq!(holidays[3].name, "Christmas, Boxing Day");
+
+        let error = parse_ics("BEGIN:VEVENT\nDTSTART:2025\nEND:VEVENT").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: invalid date \"2025\"");
+    }
+
+    #[test]
+    fn test_parse_list() {
+        let holidays = parse_list("# Ontario 2025\n\n2025-09-01 Labour Day\n2025-10-13\n").unwrap();
+        assert_eq!(holidays, [
+            Holiday { date: date(1), name: "Labour Day".to_string() },
+            Holiday { date: NaiveDate::from_ymd_opt(2025, 10, 13).unwrap(), name: "Holiday".to_string() },
+        ]);
+
+        let error = parse_list("2025-09-01 Labour Day\nSept 30 Truth and Reconciliation").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: invalid date \"Sept\"; expected YYYY-MM-DD");
+    }
+
+    #[test]
+    fn test_apply_holidays() {
+        let day = |date: NaiveDate, hours: i64, leave: i64| DaySummary {
+            date,
+            total_duration: Duration::hours(hours),
+            leave_duration: Duration::hours(leave),
+            ..DaySummary::default()
+        };
+        // Labour Day has no file and the 2nd already has a "Stat holiday" line
+        let mut summaries = vec![day(NaiveDate::from_ymd_opt(2025, 8, 29).unwrap(), 6, 0), day(date(2), 8, 8), day(date(3), 6, 0)];
+
+        let holiday = |date: NaiveDate| Holiday { date, name: format!("Day {}", date.day()) };
+        // Before tracking started, a Saturday, after today and a duplicate are all ignored
+        let mut holidays = vec![holiday(NaiveDate::from_ymd_opt(2025, 8, 1).unwrap())];
+        holidays.extend([1, 2, 6, 8, 1].map(|day| holiday(date(day))));
+        apply(&mut summaries, &holidays, &ScheduleConfig::default(), Duration::hours(8), date(5));
+
+        let dates: Vec<_> = summaries.iter().map(|day| day.date.day()).collect();
+        assert_eq!(dates, [29, 1, 2, 3]);
+        assert_eq!(summaries[1].total_duration, Duration::hours(8));
+        assert_eq!(summaries[1].leave_duration, Duration::hours(8));
+        assert_eq!(summaries[1].holiday.as_deref(), Some("Day 1"));
+        assert_eq!(summaries[2].total_duration, Duration::hours(8));
+        assert_eq!(summaries[2].holiday.as_deref(), Some("Day 2"));
+
+        // With a Tuesday to Saturday week, Saturday holidays are credited and Monday ones are not
+        let schedule = ScheduleConfig { workdays: vec![Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat], ..ScheduleConfig::default() };
+        let mut summaries = vec![day(NaiveDate::from_ymd_opt(2025, 8, 29).unwrap(), 6, 0)];
+        apply(&mut summaries, &[holiday(date(1)), holiday(date(6))], &schedule, Duration::hours(8), date(7));
+        let credited: Vec<_> = summaries.iter().filter_map(|day| day.holiday.as_deref()).collect();
+        assert_eq!(credited, ["Day 6"]);
+    }
+}
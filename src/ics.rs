@@ -0,0 +1,164 @@
+use crate::duration::DurationFormat;
+use crate::DaySummary;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use std::fmt::Write as _;
+
+/// Longest content line allowed by RFC 5545, in octets, before folding
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Escape a TEXT property value. Bare carriage returns would end the content
+/// line, so CRLF and CR line breaks become escaped newlines too.
+fn escape_text(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Append a content line, folding it into 75-octet pieces without splitting characters
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn push_categories(out: &mut String, tags: &[String]) {
+    if !tags.is_empty() {
+        let categories: Vec<_> = tags.iter().map(|tag| escape_text(tag)).collect();
+        push_line(out, &format!("CATEGORIES:{}", categories.join(",")));
+    }
+}
+
+/// A UID that stays the same across exports as long as the entry stays on the same line
+fn uid(date: NaiveDate, line: usize) -> String {
+    format!("{}-L{line}@markdown-timesheet", date.format("%Y%m%d"))
+}
+
+/// "acme: fixing bugs", "acme", "fixing bugs" or the fallback
+fn summary(project: Option<&str>, description: &str, fallback: &str) -> String {
+    match (project, description.is_empty()) {
+        (Some(project), false) => format!("{project}: {description}"),
+        (Some(project), true) => project.to_string(),
+        (None, false) => description.to_string(),
+        (None, true) => fallback.to_string(),
+    }
+}
+
+fn local_time(time: NaiveDateTime) -> String {
+    time.format("%Y%m%dT%H%M%S").to_string()
+}
+
+/// An iCalendar feed with one event per session. Sessions are floating
+/// events in local time; "Work time" entries, which have no time of day, are
+/// all-day events. Open sessions are left out and today's running session is
+/// marked tentative.
+pub fn to_ics(summaries: &[DaySummary], durations: DurationFormat, now: DateTime<Utc>) -> String {
+    let stamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, &format!("PRODID:-//markdown_timesheet//{}//EN", env!("CARGO_PKG_VERSION")));
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+
+    for day in summaries {
+        for entry in &day.entries {
+            let (Some(start_time), Some(duration)) = (entry.start_time, entry.duration()) else {
+                continue;
+            };
+            let start = day.date.and_time(start_time);
+            push_line(&mut out, "BEGIN:VEVENT");
+            push_line(&mut out, &format!("UID:{}", uid(day.date, entry.line)));
+            push_line(&mut out, &format!("DTSTAMP:{stamp}"));
+            push_line(&mut out, &format!("DTSTART:{}", local_time(start)));
+            push_line(&mut out, &format!("DTEND:{}", local_time(start + duration)));
+            push_line(&mut out, &format!("SUMMARY:{}", escape_text(&summary(entry.project(), &entry.description, "Work"))));
+            push_categories(&mut out, &entry.tags);
+            if entry.tentative {
+                push_line(&mut out, "STATUS:TENTATIVE");
+            }
+            push_line(&mut out, "END:VEVENT");
+        }
+
+        for entry in &day.work_time_entries {
+            let fallback = format!("Work time {}", durations.format(entry.duration));
+            let mut title = summary(entry.project(), &entry.description, &fallback);
+            if title != fallback {
+                let _ = write!(title, " ({})", durations.format(entry.duration));
+            }
+            push_line(&mut out, "BEGIN:VEVENT");
+            push_line(&mut out, &format!("UID:{}", uid(day.date, entry.line)));
+            push_line(&mut out, &format!("DTSTAMP:{stamp}"));
+            push_line(&mut out, &format!("DTSTART;VALUE=DATE:{}", day.date.format("%Y%m%d")));
+            push_line(&mut out, &format!("DTEND;VALUE=DATE:{}", (day.date + Duration::days(1)).format("%Y%m%d")));
+            push_line(&mut out, &format!("SUMMARY:{}", escape_text(&title)));
+            push_categories(&mut out, &entry.tags);
+            push_line(&mut out, "TRANSP:TRANSPARENT");
+            push_line(&mut out, "END:VEVENT");
+        }
+    }
+
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TimesheetParser;
+
+    fn export(content: &str) -> String {
+        let day = TimesheetParser::new(false)
+            .unwrap()
+            .parse_file(content, NaiveDate::from_ymd_opt(2025, 8, 25).unwrap())
+            .unwrap();
+        let now = DateTime::parse_from_rfc3339("2025-09-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        to_ics(&[day], DurationFormat::default(), now)
+    }
+
+    #[test]
+    fn test_sessions_are_timed_events() {
+        let ics = export("Start work 9:00 #acme fixing bugs, again\nStop work 12:30\nStart work 22:00\nStop work 1:00\nStart work 14:00");
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains(
+            "BEGIN:VEVENT\r\nUID:20250825-L1@markdown-timesheet\r\nDTSTAMP:20250901T120000Z\r\n\
+             DTSTART:20250825T090000\r\nDTEND:20250825T123000\r\nSUMMARY:acme: fixing bugs\\, again\r\n\
+             CATEGORIES:acme\r\nEND:VEVENT\r\n"
+        ));
+        assert!(ics.contains("UID:20250825-L3@markdown-timesheet\r\nDTSTAMP:20250901T120000Z\r\nDTSTART:20250825T220000\r\nDTEND:20250826T010000\r\nSUMMARY:Work\r\n"));
+        // The session that was never stopped is not exported
+        assert!(!ics.contains("L5@"));
+    }
+
+    #[test]
+    fn test_work_time_is_all_day() {
+        let ics = export("Work time 2 hours #acme code review\nWork time 30 minutes");
+        assert!(ics.contains("DTSTART;VALUE=DATE:20250825\r\nDTEND;VALUE=DATE:20250826\r\nSUMMARY:acme: code review (2h 00m)\r\n"));
+        assert!(ics.contains("UID:20250825-L2@markdown-timesheet"));
+        assert!(ics.contains("SUMMARY:Work time 0h 30m\r\n"));
+    }
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(escape_text("a\\b; c, d"), "a\\\\b\\; c\\, d");
+        assert_eq!(escape_text("one\r\ntwo\rthree\nfour"), "one\\ntwo\\nthree\\nfour");
+    }
+
+    #[test]
+    fn test_long_lines_are_folded() {
+        let mut out = String::new();
+        let line = format!("SUMMARY:{}", "é".repeat(40));
+        push_line(&mut out, &line);
+        assert!(out.lines().all(|line| line.trim_end_matches('\r').len() <= MAX_LINE_OCTETS));
+        assert_eq!(out.replace("\r\n ", ""), format!("{line}\r\n"));
+    }
+}
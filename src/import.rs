@@ -0,0 +1,304 @@
+use crate::clock::{self, Clock};
+use crate::diff::unified_diff;
+use crate::error::TimesheetError;
+use crate::TimesheetParser;
+use chrono::{NaiveDate, NaiveTime, Timelike};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// A session read from another time tracker, ready to be written as entries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedSession {
+    pub date: NaiveDate,
+    pub start: NaiveTime,
+    /// `None` for a session that is still being tracked
+    pub end: Option<NaiveTime>,
+    pub tags: Vec<String>,
+    pub description: String,
+}
+
+impl ImportedSession {
+    /// The "Start work" and "Stop work" lines for this session, with tags
+    /// and description on the start line
+    fn entry_lines(&self) -> Vec<String> {
+        let mut start = clock::entry_line(Clock::Start, self.start);
+        for tag in &self.tags {
+            start.push_str(" #");
+            start.push_str(tag);
+        }
+        if !self.description.is_empty() {
+            start.push(' ');
+            start.push_str(&self.description);
+        }
+
+        let mut lines = vec![start];
+        lines.extend(self.end.map(|end| clock::entry_line(Clock::Stop, end)));
+        lines
+    }
+}
+
+/// A tag from another tool as a `#tag` this parser recognises, or `None`
+/// when it cannot become one (e.g. it doesn't start with a letter)
+pub fn tag_name(tag: &str) -> Option<String> {
+    let name: String = tag
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    let name = name.trim_matches('-');
+    name.starts_with(|c: char| c.is_ascii_alphabetic()).then(|| name.to_string())
+}
+
+/// Tags that can be written as `#tags`, plus the rest as plain words for the description
+pub fn split_tags<'a>(tags: impl IntoIterator<Item = &'a str>) -> (Vec<String>, Vec<&'a str>) {
+    let mut names = Vec::new();
+    let mut words = Vec::new();
+    for tag in tags {
+        match tag_name(tag) {
+            Some(name) if !names.contains(&name) => names.push(name),
+            Some(_) => {}
+            None => words.push(tag.trim()),
+        }
+    }
+    (names, words)
+}
+
+/// `time` without its seconds. Entries are written in whole minutes, so
+/// imported times must be too or the same session would not match on re-import.
+pub fn whole_minutes<T: Timelike + Copy>(time: T) -> T {
+    time.with_second(0).and_then(|time| time.with_nanosecond(0)).expect("second zero always exists")
+}
+
+/// The new content for one daily file
+#[derive(Debug)]
+pub struct FileUpdate {
+    pub path: PathBuf,
+    /// The file's content before the import, empty for a new file
+    pub original: String,
+    pub updated: String,
+    /// Number of imported sessions appended to the file or closing an open one
+    pub added: usize,
+}
+
+/// Work out what each daily file looks like with the imported sessions
+/// appended. Sessions whose start and stop times are already in the file are
+/// skipped, so importing the same data twice changes nothing; a running
+/// session matches an open start at the same time. A finished session whose
+/// start is open in the file gets its stop inserted under that start instead.
+/// Files without new sessions are left out.
+pub fn plan_merge(directory: &Path, sessions: &[ImportedSession]) -> Result<Vec<FileUpdate>, TimesheetError> {
+    let parser = TimesheetParser::new(false)?;
+    let mut by_date: BTreeMap<NaiveDate, Vec<&ImportedSession>> = BTreeMap::new();
+    for session in sessions {
+        by_date.entry(session.date).or_default().push(session);
+    }
+
+    let mut updates = Vec::new();
+    for (date, mut sessions) in by_date {
+        sessions.sort_by_key(|session| session.start);
+        let path = directory.join(format!("{date}.md"));
+        let original = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(TimesheetError::io(&path, error)),
+        };
+
+        let existing = parser.parse_file(&original, date).map_err(|error| error.with_path(&path))?;
+        // Today's running session has a tentative end that is not in the file
+        let mut present: Vec<_> = existing
+            .entries
+            .iter()
+            .map(|entry| (entry.start_time, entry.end_time.filter(|_| !entry.tentative)))
+            .collect();
+        let mut open: Vec<_> = existing
+            .entries
+            .iter()
+            .filter(|entry| entry.tentative || entry.end_time.is_none())
+            .filter_map(|entry| Some((entry.start_time?, entry.line - 1)))
+            .collect();
+
+        let mut lines: Vec<String> = original.split_inclusive('\n').map(str::to_string).collect();
+        let mut stops: BTreeMap<usize, String> = BTreeMap::new();
+        let mut appended = Vec::new();
+        let mut added = 0;
+        for session in sessions {
+            let times = (Some(session.start), session.end);
+            let running_and_started = session.end.is_none() && present.iter().any(|(start, _)| *start == times.0);
+            if present.contains(&times) || running_and_started {
+                continue;
+            }
+            present.push(times);
+            added += 1;
+
+            let closes = open.iter().position(|(start, _)| *start == session.start).filter(|_| session.end.is_some());
+            match (closes, session.end) {
+                (Some(position), Some(end)) => {
+                    // Before the next entry, so the notes under the start stay with the session
+                    let (_, line) = open.remove(position);
+                    let next = (line + 1..lines.len()).find(|&index| parser.is_entry(&lines[index])).unwrap_or(lines.len());
+                    stops.insert(next, clock::entry_line(Clock::Stop, end));
+                }
+                _ => appended.extend(session.entry_lines()),
+            }
+        }
+
+        let newline = if original.contains("\r\n") { "\r\n" } else { "\n" };
+        if let Some(last) = lines.last_mut().filter(|last| !last.ends_with('\n')) {
+            last.push_str(newline);
+        }
+        let mut content = String::new();
+        for (index, line) in lines.iter().enumerate() {
+            if let Some(stop) = stops.remove(&index) {
+                content.push_str(&stop);
+                content.push_str(newline);
+            }
+            content.push_str(line);
+        }
+        for line in stops.into_values().chain(appended) {
+            content.push_str(&line);
+            content.push_str(newline);
+        }
+
+        if added > 0 {
+            updates.push(FileUpdate {
+                path,
+                original,
+                updated: content,
+                added,
+            });
+        }
+    }
+    Ok(updates)
+}
+
+impl FileUpdate {
+    /// What the import changes in this file, as a unified diff
+    pub fn diff(&self) -> String {
+        let new_label = self.path.display().to_string();
+        let old_label = if self.original.is_empty() && !self.path.exists() { "/dev/null".to_string() } else { new_label.clone() };
+        unified_diff(&old_label, &new_label, &self.original, &self.updated)
+    }
+}
+
+pub fn write_updates(updates: &[FileUpdate]) -> Result<(), TimesheetError> {
+    for update in updates {
+        fs::write(&update.path, &update.updated).map_err(|error| TimesheetError::io(&update.path, error))?;
+    }
+    Ok(())
+}
+
+/// Merge imported sessions into the daily files in `directory`, or with
+/// `dry_run` only print the diff of every file that would change
+pub fn import_sessions(directory: &Path, sessions: &[ImportedSession], dry_run: bool) -> Result<(), TimesheetError> {
+    let updates = plan_merge(directory, sessions)?;
+    if dry_run {
+        for update in &updates {
+            print!("{}", update.diff());
+        }
+    } else {
+        write_updates(&updates)?;
+    }
+
+    let added: usize = updates.iter().map(|update| update.added).sum();
+    let files = updates.len();
+    let file_noun = if files == 1 { "file" } else { "files" };
+    let session_noun = if added == 1 { "session" } else { "sessions" };
+    let verb = if dry_run { "Would import" } else { "Imported" };
+    println!("{verb} {added} {session_noun} into {files} {file_noun}");
+    let present = sessions.len() - added;
+    if present > 0 {
+        println!("{present} already present");
+    }
+    Ok(())
+}
+
+/// The contents of `path`, or standard input when no path is given
+pub fn read_input(path: Option<&Path>) -> Result<String, TimesheetError> {
+    if let Some(path) = path {
+        return fs::read_to_string(path).map_err(|error| TimesheetError::io(path, error));
+    }
+    let mut input = String::new();
+    io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|error| TimesheetError::io(Path::new("<stdin>"), error))?;
+    Ok(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(start: (u32, u32), end: Option<(u32, u32)>) -> ImportedSession {
+        ImportedSession {
+            date: NaiveDate::from_ymd_opt(2025, 8, 25).unwrap(),
+            start: NaiveTime::from_hms_opt(start.0, start.1, 0).unwrap(),
+            end: end.map(|(hour, minute)| NaiveTime::from_hms_opt(hour, minute, 0).unwrap()),
+            tags: vec!["acme".to_string()],
+            description: "design review".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_tag_name() {
+        assert_eq!(tag_name("Acme"), Some("acme".to_string()));
+        assert_eq!(tag_name("client work"), Some("client-work".to_string()));
+        assert_eq!(tag_name("acme.web"), Some("acme-web".to_string()));
+        assert_eq!(tag_name("2025"), None);
+        assert_eq!(tag_name("  "), None);
+
+        let (names, words) = split_tags(["Acme", "acme", "42"]);
+        assert_eq!(names, ["acme"]);
+        assert_eq!(words, ["42"]);
+    }
+
+    #[test]
+    fn test_entry_lines() {
+        assert_eq!(session((9, 0), Some((12, 30))).entry_lines(), ["Start work 9:00 #acme design review", "Stop work 12:30"]);
+        assert_eq!(session((9, 0), None).entry_lines(), ["Start work 9:00 #acme design review"]);
+    }
+
+    #[test]
+    fn test_plan_merge_keeps_notes_and_skips_duplicates() {
+        let directory = std::env::temp_dir().join(format!("markdown_timesheet_import_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("2025-08-25.md");
+        fs::write(&path, "# Notes\nStart work 9:00\nStop work 12:30\nLunch with the team").unwrap();
+
+        let sessions = [session((9, 0), Some((12, 30))), session((13, 0), Some((17, 0)))];
+        let updates = plan_merge(&directory, &sessions).unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].added, 1);
+        assert_eq!(
+            updates[0].updated,
+            "# Notes\nStart work 9:00\nStop work 12:30\nLunch with the team\nStart work 13:00 #acme design review\nStop work 17:00\n"
+        );
+
+        assert!(updates[0].diff().contains("@@ -2,3 +2,5 @@\n Start work 9:00\n Stop work 12:30\n Lunch with the team\n+Start work 13:00"));
+
+        write_updates(&updates).unwrap();
+        assert!(plan_merge(&directory, &sessions).unwrap().is_empty());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_plan_merge_closes_running_session() {
+        let directory = std::env::temp_dir().join(format!("markdown_timesheet_import_open_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("2025-08-25.md");
+        fs::write(&path, "Start work 9:00\nPlanning\nWork time 1 hour\n").unwrap();
+
+        // Still running in the other tracker: the open start is the same session
+        assert!(plan_merge(&directory, &[session((9, 0), None)]).unwrap().is_empty());
+
+        let updates = plan_merge(&directory, &[session((9, 0), Some((12, 0))), session((13, 0), None)]).unwrap();
+        assert_eq!(updates[0].added, 2);
+        assert_eq!(updates[0].updated, "Start work 9:00\nPlanning\nStop work 12:00\nWork time 1 hour\nStart work 13:00 #acme design review\n");
+        write_updates(&updates).unwrap();
+        assert!(plan_merge(&directory, &[session((9, 0), Some((12, 0))), session((13, 0), None)]).unwrap().is_empty());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
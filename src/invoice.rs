@@ -0,0 +1,718 @@
+use crate::cli::{InvoiceArgs, InvoiceFormat, LineItemGrouping};
+use crate::config::{self, ClientConfig, Config, MoneyRounding, TaxConfig};
+use crate::error::TimesheetError;
+use crate::rounding::{RoundingRule, RoundingScope};
+use crate::duration::DurationFormat;
+use crate::DaySummary;
+use chrono::{Local, NaiveDate};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineItem {
+    pub date: NaiveDate,
+    pub description: String,
+    /// Minutes actually worked
+    pub minutes: i64,
+    /// Minutes charged after the rounding rule, the same as `minutes` without one
+    pub billed_minutes: i64,
+    pub rate_cents: i64,
+    pub amount_cents: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaxLine {
+    pub name: String,
+    pub rate: f64,
+    pub amount_cents: i64,
+}
+
+#[derive(Debug)]
+pub struct Invoice {
+    pub number: String,
+    pub issue_date: NaiveDate,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub issuer: Option<String>,
+    pub client_name: String,
+    pub client_address: Option<String>,
+    pub currency: String,
+    pub items: Vec<LineItem>,
+    pub subtotal_cents: i64,
+    pub taxes: Vec<TaxLine>,
+    pub total_cents: i64,
+    pub draft: bool,
+}
+
+/// A completed session or "Work time" entry that can go on an invoice
+struct BillableTime {
+    date: NaiveDate,
+    project: String,
+    description: String,
+    minutes: i64,
+}
+
+/// Raw and billed minutes of one line item before rates are applied
+struct LineTotals {
+    description: String,
+    minutes: i64,
+    billed_minutes: i64,
+}
+
+/// Line items are ordered by date, then project, then the order the time was logged in
+type LineKey = (NaiveDate, String, usize);
+
+/// What to bill and how to lay it out, resolved from the command line and configuration
+pub struct InvoiceRequest<'a> {
+    pub client_key: &'a str,
+    pub client: &'a ClientConfig,
+    pub project: Option<&'a str>,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub grouping: LineItemGrouping,
+    pub rounding: Option<RoundingRule>,
+}
+
+/// "09:00–12:00 acme: fixing the login page", or just the heading without a description
+fn session_description(heading: &str, description: &str) -> String {
+    if description.is_empty() {
+        heading.to_string()
+    } else {
+        format!("{heading}: {description}")
+    }
+}
+
+/// Completed work for the request's client and date range. Tentative
+/// sessions are left out because their end time is not known yet.
+fn billable_time(summaries: &[DaySummary], request: &InvoiceRequest) -> Vec<BillableTime> {
+    let projects = request.client.projects(request.client_key);
+    let wanted = |project: &&str| {
+        projects.iter().any(|candidate| candidate == project)
+            && request.project.is_none_or(|wanted| wanted.eq_ignore_ascii_case(project))
+    };
+
+    let mut billable = Vec::new();
+    for day in summaries.iter().filter(|day| day.date >= request.from && day.date <= request.to) {
+        for entry in day.entries.iter().filter(|entry| !entry.tentative) {
+            let (Some(project), Some(duration), Some(start), Some(end)) =
+                (entry.project().filter(wanted), entry.duration(), entry.start_time, entry.end_time)
+            else {
+                continue;
+            };
+            billable.push(BillableTime {
+                date: day.date,
+                project: project.to_string(),
+                description: session_description(
+                    &format!("{}–{} {project}", start.format("%H:%M"), end.format("%H:%M")),
+                    &entry.description,
+                ),
+                minutes: duration.num_minutes(),
+            });
+        }
+        for entry in &day.work_time_entries {
+            let Some(project) = entry.project().filter(wanted) else {
+                continue;
+            };
+            billable.push(BillableTime {
+                date: day.date,
+                project: project.to_string(),
+                description: session_description(&format!("Work time {project}"), &entry.description),
+                minutes: entry.duration.num_minutes(),
+            });
+        }
+    }
+    billable
+}
+
+fn to_cents(value: f64) -> i64 {
+    #[allow(clippy::cast_possible_truncation)]
+    let cents = (value * 100.0).round() as i64;
+    cents
+}
+
+/// Divide rounding half away from zero; rounding adjustments can make amounts negative
+fn div_round(numerator: i64, denominator: i64) -> i64 {
+    if numerator < 0 {
+        -div_round(-numerator, denominator)
+    } else {
+        (numerator + denominator / 2) / denominator
+    }
+}
+
+/// Apply a per-day or per-period rounding rule to the grouped line items. A
+/// group with a single line item is adjusted in place; otherwise the
+/// difference gets its own "Rounding adjustment" line after the group.
+fn round_groups(lines: &mut BTreeMap<LineKey, LineTotals>, rule: RoundingRule, period_end: NaiveDate) {
+    let mut groups: BTreeMap<(Option<NaiveDate>, String), Vec<LineKey>> = BTreeMap::new();
+    for key in lines.keys() {
+        let date = (rule.scope == RoundingScope::Day).then_some(key.0);
+        groups.entry((date, key.1.clone())).or_default().push(key.clone());
+    }
+
+    for ((date, project), keys) in groups {
+        let minutes: i64 = keys.iter().map(|key| lines[key].minutes).sum();
+        let adjustment = rule.round(minutes) - minutes;
+        if adjustment == 0 {
+            continue;
+        }
+        if let [key] = keys.as_slice() {
+            if let Some(line) = lines.get_mut(key) {
+                line.billed_minutes += adjustment;
+            }
+        } else {
+            let totals = LineTotals {
+                description: format!("Rounding adjustment {project}"),
+                minutes: 0,
+                billed_minutes: adjustment,
+            };
+            lines.insert((date.unwrap_or(period_end), project, usize::MAX), totals);
+        }
+    }
+}
+
+fn hourly_rate_cents(config: &Config, request: &InvoiceRequest, project: &str) -> Result<i64, TimesheetError> {
+    request
+        .client
+        .project_rates
+        .get(project)
+        .copied()
+        .or(request.client.rate)
+        .or(config.invoice.rate)
+        .map(to_cents)
+        .ok_or_else(|| {
+            TimesheetError::config(
+                format!("clients.{}", request.client_key),
+                format!("no hourly rate for project \"{project}\"; set `rate` for the client or in [invoice]"),
+            )
+        })
+}
+
+pub fn build_invoice(
+    summaries: &[DaySummary],
+    config: &Config,
+    request: &InvoiceRequest,
+    number: String,
+    draft: bool,
+) -> Result<Invoice, TimesheetError> {
+    let session_rule = request.rounding.filter(|rule| rule.scope == RoundingScope::Session);
+
+    let mut grouped: BTreeMap<LineKey, LineTotals> = BTreeMap::new();
+    for (index, time) in billable_time(summaries, request).into_iter().enumerate() {
+        let (key, description) = match request.grouping {
+            LineItemGrouping::Day => ((time.date, time.project.clone(), 0), time.project.clone()),
+            LineItemGrouping::Session => ((time.date, time.project.clone(), index), time.description),
+        };
+        let line = grouped.entry(key).or_insert_with(|| LineTotals {
+            description,
+            minutes: 0,
+            billed_minutes: 0,
+        });
+        line.minutes += time.minutes;
+        line.billed_minutes += session_rule.map_or(time.minutes, |rule| rule.round(time.minutes));
+    }
+
+    if grouped.is_empty() {
+        return Err(TimesheetError::Usage(format!(
+            "no billable time for client \"{}\" between {} and {}",
+            request.client_key, request.from, request.to
+        )));
+    }
+
+    if let Some(rule) = request.rounding.filter(|rule| rule.scope != RoundingScope::Session) {
+        round_groups(&mut grouped, rule, request.to);
+    }
+
+    let mut items = Vec::new();
+    let mut exact_subtotal = 0; // in cents × 60
+    for ((date, project, _), line) in grouped {
+        let rate_cents = hourly_rate_cents(config, request, &project)?;
+        exact_subtotal += line.billed_minutes * rate_cents;
+        items.push(LineItem {
+            date,
+            description: line.description,
+            minutes: line.minutes,
+            billed_minutes: line.billed_minutes,
+            rate_cents,
+            amount_cents: div_round(line.billed_minutes * rate_cents, 60),
+        });
+    }
+
+    let subtotal_cents = match config.invoice.rounding {
+        MoneyRounding::PerLine => items.iter().map(|item| item.amount_cents).sum(),
+        MoneyRounding::Total => div_round(exact_subtotal, 60),
+    };
+
+    let taxes: Vec<_> = request
+        .client
+        .taxes
+        .as_ref()
+        .unwrap_or(&config.invoice.taxes)
+        .iter()
+        .map(|TaxConfig { name, rate }| TaxLine {
+            name: name.clone(),
+            rate: *rate,
+            // Tax rates are kept to a hundredth of a percent
+            amount_cents: div_round(subtotal_cents * to_cents(*rate), 10_000),
+        })
+        .collect();
+    let total_cents = subtotal_cents + taxes.iter().map(|tax| tax.amount_cents).sum::<i64>();
+
+    Ok(Invoice {
+        number,
+        issue_date: Local::now().date_naive(),
+        from: request.from,
+        to: request.to,
+        issuer: config.invoice.issuer.clone(),
+        client_name: request.client.name.clone().unwrap_or_else(|| request.client_key.to_string()),
+        client_address: request.client.address.clone(),
+        currency: request.client.currency.clone().unwrap_or_else(|| config.invoice.currency.clone()),
+        items,
+        subtotal_cents,
+        taxes,
+        total_cents,
+        draft,
+    })
+}
+
+/// "1,234.56" from 123456 cents
+fn format_money(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let cents = cents.abs();
+    let whole = (cents / 100).to_string();
+    let mut grouped = String::new();
+    for (index, digit) in whole.chars().enumerate() {
+        if index > 0 && (whole.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    format!("{sign}{grouped}.{:02}", cents % 100)
+}
+
+fn format_hours(minutes: i64) -> String {
+    let hundredths = div_round(minutes * 100, 60);
+    let sign = if hundredths < 0 { "-" } else { "" };
+    format!("{sign}{}.{:02}", hundredths.abs() / 100, hundredths.abs() % 100)
+}
+
+/// Billed hours, followed by the hours worked when rounding changed them
+fn format_item_hours(item: &LineItem) -> String {
+    if item.billed_minutes == item.minutes || item.minutes == 0 {
+        format_hours(item.billed_minutes)
+    } else {
+        format!("{} ({} worked)", format_hours(item.billed_minutes), format_hours(item.minutes))
+    }
+}
+
+/// The hours row of the totals table, e.g. "3.50 (3h 30m)" or "3.50 billed, 3h 20m worked"
+fn format_total_hours(invoice: &Invoice, durations: DurationFormat) -> String {
+    let minutes: i64 = invoice.items.iter().map(|item| item.minutes).sum();
+    let billed_minutes: i64 = invoice.items.iter().map(|item| item.billed_minutes).sum();
+    if billed_minutes == minutes {
+        format!("{} ({})", format_hours(minutes), durations.format(chrono::Duration::minutes(minutes)))
+    } else {
+        format!(
+            "{} billed, {} worked",
+            format_hours(billed_minutes),
+            durations.format(chrono::Duration::minutes(minutes))
+        )
+    }
+}
+
+fn format_rate(rate: f64) -> String {
+    let formatted = format!("{rate:.2}");
+    formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+pub fn render_markdown(invoice: &Invoice, durations: DurationFormat) -> String {
+    let mut out = String::new();
+    let currency = &invoice.currency;
+    let draft = if invoice.draft { " (draft)" } else { "" };
+
+    let _ = writeln!(out, "# Invoice {}{draft}\n", invoice.number);
+    let _ = writeln!(out, "**Date:** {}  ", invoice.issue_date);
+    let _ = writeln!(out, "**Period:** {} to {}\n", invoice.from, invoice.to);
+    if let Some(issuer) = &invoice.issuer {
+        let _ = writeln!(out, "**From:**  ");
+        for line in issuer.lines() {
+            let _ = writeln!(out, "{line}  ");
+        }
+        out.push('\n');
+    }
+    let _ = writeln!(out, "**Bill to:**  ");
+    let _ = writeln!(out, "{}  ", invoice.client_name);
+    for line in invoice.client_address.iter().flat_map(|address| address.lines()) {
+        let _ = writeln!(out, "{line}  ");
+    }
+
+    let _ = writeln!(out, "\n| Date | Description | Hours | Rate ({currency}) | Amount ({currency}) |");
+    let _ = writeln!(out, "|------|-------------|------:|------:|-------:|");
+    for item in &invoice.items {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} | {} |",
+            item.date,
+            item.description.replace('|', "\\|"),
+            format_item_hours(item),
+            format_money(item.rate_cents),
+            format_money(item.amount_cents)
+        );
+    }
+
+    let _ = writeln!(out, "\n| | |");
+    let _ = writeln!(out, "|---|---:|");
+    let _ = writeln!(out, "| Hours | {} |", format_total_hours(invoice, durations));
+    let _ = writeln!(out, "| Subtotal | {currency} {} |", format_money(invoice.subtotal_cents));
+    for tax in &invoice.taxes {
+        let _ = writeln!(out, "| {} ({}%) | {currency} {} |", tax.name, format_rate(tax.rate), format_money(tax.amount_cents));
+    }
+    let _ = writeln!(out, "| **Total** | **{currency} {}** |", format_money(invoice.total_cents));
+    out
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const INVOICE_CSS: &str = "body { font-family: -apple-system, 'Segoe UI', Helvetica, Arial, sans-serif; color: #222; max-width: 52rem; margin: 2rem auto; padding: 0 1rem; }
+h1 { font-size: 1.8rem; margin-bottom: 0.25rem; }
+.meta, .parties { display: flex; gap: 3rem; margin: 1rem 0; }
+.parties h2 { font-size: 0.8rem; text-transform: uppercase; color: #666; margin: 0 0 0.25rem; }
+table { width: 100%; border-collapse: collapse; margin-top: 1.5rem; }
+th, td { padding: 0.4rem 0.6rem; border-bottom: 1px solid #ddd; text-align: left; }
+th { background: #f4f4f4; }
+.num { text-align: right; font-variant-numeric: tabular-nums; }
+.totals { width: auto; margin-left: auto; }
+.totals .grand td { font-weight: bold; border-top: 2px solid #222; }
+.draft { color: #b00; }
+";
+
+const PRINT_CSS: &str = "@page { size: A4; margin: 18mm; }
+@media print { body { margin: 0; max-width: none; font-size: 10pt; } th { background: none; } tr { page-break-inside: avoid; } }
+";
+
+/// Self-contained HTML; `print` adds page setup so a browser's "Save as PDF" gives a clean A4 invoice
+pub fn render_html(invoice: &Invoice, durations: DurationFormat, print: bool) -> String {
+    let mut out = String::new();
+    let currency = escape_html(&invoice.currency);
+    let number = escape_html(&invoice.number);
+
+    let _ = writeln!(out, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">");
+    let _ = writeln!(out, "<title>Invoice {number}</title>");
+    let _ = writeln!(out, "<style>\n{INVOICE_CSS}{}</style>\n</head>\n<body>", if print { PRINT_CSS } else { "" });
+    let draft = if invoice.draft { " <span class=\"draft\">(draft)</span>" } else { "" };
+    let _ = writeln!(out, "<h1>Invoice {number}{draft}</h1>");
+    let _ = writeln!(
+        out,
+        "<div class=\"meta\"><div>Date: {}</div><div>Period: {} to {}</div></div>",
+        invoice.issue_date, invoice.from, invoice.to
+    );
+
+    let _ = writeln!(out, "<div class=\"parties\">");
+    if let Some(issuer) = &invoice.issuer {
+        let lines: Vec<_> = issuer.lines().map(escape_html).collect();
+        let _ = writeln!(out, "<div><h2>From</h2>{}</div>", lines.join("<br>"));
+    }
+    let mut client_lines = vec![escape_html(&invoice.client_name)];
+    client_lines.extend(invoice.client_address.iter().flat_map(|address| address.lines()).map(escape_html));
+    let _ = writeln!(out, "<div><h2>Bill to</h2>{}</div>", client_lines.join("<br>"));
+    let _ = writeln!(out, "</div>");
+
+    let _ = writeln!(out, "<table>\n<thead><tr><th>Date</th><th>Description</th><th class=\"num\">Hours</th><th class=\"num\">Rate ({currency})</th><th class=\"num\">Amount ({currency})</th></tr></thead>\n<tbody>");
+    for item in &invoice.items {
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+            item.date,
+            escape_html(&item.description),
+            format_item_hours(item),
+            format_money(item.rate_cents),
+            format_money(item.amount_cents)
+        );
+    }
+    let _ = writeln!(out, "</tbody>\n</table>");
+
+    let _ = writeln!(out, "<table class=\"totals\">");
+    let _ = writeln!(out, "<tr><td>Hours</td><td class=\"num\">{}</td></tr>", format_total_hours(invoice, durations));
+    let _ = writeln!(out, "<tr><td>Subtotal</td><td class=\"num\">{currency} {}</td></tr>", format_money(invoice.subtotal_cents));
+    for tax in &invoice.taxes {
+        let _ = writeln!(
+            out,
+            "<tr><td>{} ({}%)</td><td class=\"num\">{currency} {}</td></tr>",
+            escape_html(&tax.name),
+            format_rate(tax.rate),
+            format_money(tax.amount_cents)
+        );
+    }
+    let _ = writeln!(out, "<tr class=\"grand\"><td>Total</td><td class=\"num\">{currency} {}</td></tr>", format_money(invoice.total_cents));
+    let _ = writeln!(out, "</table>\n</body>\n</html>");
+    out
+}
+
+/// The last invoice number issued for `directory`, or 0 before the first invoice
+fn last_invoice_number(directory: &Path) -> Result<u32, TimesheetError> {
+    let path = config::invoice_sequence_path(directory);
+    match fs::read_to_string(&path) {
+        Ok(content) => content.trim().parse().map_err(|_| {
+            TimesheetError::config(path.display().to_string(), "expected the last invoice number")
+        }),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(0),
+        Err(error) => Err(TimesheetError::io(&path, error)),
+    }
+}
+
+fn store_invoice_number(directory: &Path, number: u32) -> Result<(), TimesheetError> {
+    let path = config::invoice_sequence_path(directory);
+    fs::write(&path, format!("{number}\n")).map_err(|error| TimesheetError::io(&path, error))
+}
+
+/// Build and write an invoice, advancing the local number sequence unless
+/// it is a draft or an explicit number was given.
+pub fn run(args: &InvoiceArgs, config: &Config, durations: DurationFormat, summaries: &[DaySummary]) -> Result<(), TimesheetError> {
+    let directory = &args.common.directory;
+    let client = config.clients.get(&args.client).ok_or_else(|| {
+        TimesheetError::config("--client", format!("no [clients.{}] section in the configuration", args.client))
+    })?;
+
+    let sequence_number = match args.number {
+        Some(number) => number,
+        None => last_invoice_number(directory)? + 1,
+    };
+    let number = format!("{}{sequence_number:04}", config.invoice.number_prefix);
+
+    let request = InvoiceRequest {
+        client_key: &args.client,
+        client,
+        project: args.project.as_deref(),
+        from: args.from,
+        to: args.to,
+        grouping: args.by,
+        rounding: if args.no_rounding { None } else { client.rounding.or(config.rounding) },
+    };
+    let invoice = build_invoice(summaries, config, &request, number, args.draft)?;
+
+    let rendered = match args.format {
+        InvoiceFormat::Markdown => render_markdown(&invoice, durations),
+        InvoiceFormat::Html => render_html(&invoice, durations, false),
+        InvoiceFormat::PrintHtml => render_html(&invoice, durations, true),
+    };
+
+    match &args.output {
+        Some(path) => {
+            fs::write(path, rendered).map_err(|error| TimesheetError::io(path, error))?;
+            eprintln!("Invoice {} written to {}", invoice.number, path.display());
+        }
+        None => print!("{rendered}"),
+    }
+
+    if !args.draft && args.number.is_none() {
+        store_invoice_number(directory, sequence_number)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TimesheetParser;
+
+    const CONFIG: &str = r#"
+[invoice]
+issuer = "Jane Doe\n1 Main St"
+currency = "CAD"
+rate = 90
+taxes = [{ name = "GST", rate = 5 }]
+
+[clients.acme]
+name = "Acme <Corp>"
+projects = ["acme", "acme-web"]
+project_rates = { acme-web = 120 }
+"#;
+
+    fn summaries() -> Vec<DaySummary> {
+        let parser = TimesheetParser::new(false).unwrap();
+        let day = |date: &str, content: &str| parser.parse_file(content, date.parse().unwrap()).unwrap();
+        vec![
+            day("2025-08-25", "Start work 9:00 #acme\nStop work 12:20\nStart work 13:00 #acme-web\nStop work 14:00\nStart work 14:00 #other\nStop work 15:00"),
+            day("2025-08-26", "Start work 9:00 #acme\nStop work 10:00\nWork time 30 minutes #acme docs\nStart work 11:00\nStop work 12:00"),
+            day("2025-09-01", "Start work 9:00 #acme\nStop work 17:00"),
+        ]
+    }
+
+    fn request<'a>(config: &'a Config, grouping: LineItemGrouping) -> InvoiceRequest<'a> {
+        InvoiceRequest {
+            client_key: "acme",
+            client: &config.clients["acme"],
+            project: None,
+            from: "2025-08-01".parse().unwrap(),
+            to: "2025-08-31".parse().unwrap(),
+            grouping,
+            rounding: None,
+        }
+    }
+
+    #[test]
+    fn test_invoice_by_day() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+        let invoice = build_invoice(&summaries(), &config, &request(&config, LineItemGrouping::Day), "INV-0001".into(), false).unwrap();
+
+        let items: Vec<_> = invoice.items.iter().map(|item| (item.date.to_string(), item.description.as_str(), item.minutes, item.amount_cents)).collect();
+        assert_eq!(
+            items,
+            [
+                ("2025-08-25".to_string(), "acme", 200, 30_000),
+                ("2025-08-25".to_string(), "acme-web", 60, 12_000),
+                ("2025-08-26".to_string(), "acme", 90, 13_500),
+            ]
+        );
+        assert_eq!(invoice.subtotal_cents, 55_500);
+        assert_eq!(invoice.taxes[0].amount_cents, 2_775);
+        assert_eq!(invoice.total_cents, 58_275);
+        assert_eq!(invoice.currency, "CAD");
+    }
+
+    #[test]
+    fn test_invoice_by_session_and_project() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+        let mut request = request(&config, LineItemGrouping::Session);
+        request.project = Some("acme");
+        let invoice = build_invoice(&summaries(), &config, &request, "INV-0001".into(), false).unwrap();
+
+        let descriptions: Vec<_> = invoice.items.iter().map(|item| item.description.as_str()).collect();
+        assert_eq!(descriptions, ["09:00–12:20 acme", "09:00–10:00 acme", "Work time acme: docs"]);
+    }
+
+    #[test]
+    fn test_money_rounding() {
+        let mut config: Config = toml::from_str(CONFIG).unwrap();
+        config.invoice.rate = Some(100.0 / 3.0);
+        config.invoice.taxes.clear();
+        let summaries = vec![TimesheetParser::new(false)
+            .unwrap()
+            .parse_file("Work time 1 minutes #acme\nWork time 1 minutes #acme", "2025-08-25".parse().unwrap())
+            .unwrap()];
+
+        // Each minute is worth 55.55… cents
+        let per_line = build_invoice(&summaries, &config, &request(&config, LineItemGrouping::Session), String::new(), false).unwrap();
+        assert_eq!(per_line.subtotal_cents, 112);
+
+        config.invoice.rounding = MoneyRounding::Total;
+        let total = build_invoice(&summaries, &config, &request(&config, LineItemGrouping::Session), String::new(), false).unwrap();
+        assert_eq!(total.subtotal_cents, 111);
+    }
+
+    #[test]
+    fn test_rounding_scopes() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+        let billed = |rounding: &str, grouping| {
+            let mut request = request(&config, grouping);
+            request.rounding = Some(toml::from_str(rounding).unwrap());
+            let invoice = build_invoice(&summaries(), &config, &request, String::new(), false).unwrap();
+            invoice.items.iter().map(|item| (item.description.clone(), item.minutes, item.billed_minutes)).collect::<Vec<_>>()
+        };
+        let line = |description: &str, minutes, billed_minutes| (description.to_string(), minutes, billed_minutes);
+
+        // 200 + 60 minutes on the 25th, 60 + 30 on the 26th
+        assert_eq!(
+            billed("increment = 15\nmode = \"up\"", LineItemGrouping::Session),
+            [
+                line("09:00–12:20 acme", 200, 210),
+                line("13:00–14:00 acme-web", 60, 60),
+                line("09:00–10:00 acme", 60, 60),
+                line("Work time acme: docs", 30, 30),
+            ]
+        );
+        assert_eq!(
+            billed("increment = 60\nscope = \"day\"", LineItemGrouping::Session),
+            [
+                line("09:00–12:20 acme", 200, 180),
+                line("13:00–14:00 acme-web", 60, 60),
+                line("09:00–10:00 acme", 60, 60),
+                line("Work time acme: docs", 30, 30),
+                line("Rounding adjustment acme", 0, 30),
+            ]
+        );
+        assert_eq!(
+            billed("increment = 60\nmode = \"down\"\nscope = \"period\"", LineItemGrouping::Day),
+            [
+                line("acme", 200, 200),
+                line("acme-web", 60, 60),
+                line("acme", 90, 90),
+                line("Rounding adjustment acme", 0, -50),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_rounded_hours() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+        let mut request = request(&config, LineItemGrouping::Day);
+        request.rounding = Some(toml::from_str("increment = 15\nmode = \"up\"").unwrap());
+        let invoice = build_invoice(&summaries(), &config, &request, String::new(), false).unwrap();
+
+        let markdown = render_markdown(&invoice, DurationFormat::default());
+        assert!(markdown.contains("| 2025-08-25 | acme | 3.50 (3.33 worked) | 90.00 | 315.00 |"));
+        assert!(markdown.contains("| 2025-08-25 | acme-web | 1.00 | 120.00 | 120.00 |"));
+        assert!(markdown.contains("| Hours | 6.00 billed, 5h 50m worked |"));
+        assert_eq!(format_money(-4_500), "-45.00");
+        assert_eq!(format_hours(-50), "-0.83");
+    }
+
+    #[test]
+    fn test_missing_rate_and_empty_invoice() {
+        let config: Config = toml::from_str("[clients.acme]\nname = \"Acme\"").unwrap();
+        let error = build_invoice(&summaries(), &config, &request(&config, LineItemGrouping::Day), String::new(), false).unwrap_err();
+        assert!(matches!(error, TimesheetError::Config { .. }));
+
+        let config: Config = toml::from_str(CONFIG).unwrap();
+        let mut request = request(&config, LineItemGrouping::Day);
+        request.from = "2024-01-01".parse().unwrap();
+        request.to = "2024-01-31".parse().unwrap();
+        let error = build_invoice(&summaries(), &config, &request, String::new(), false).unwrap_err();
+        assert!(matches!(error, TimesheetError::Usage(_)));
+    }
+
+    #[test]
+    fn test_render() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+        let invoice = build_invoice(&summaries(), &config, &request(&config, LineItemGrouping::Day), "INV-0042".into(), true).unwrap();
+
+        let markdown = render_markdown(&invoice, DurationFormat::default());
+        assert!(markdown.starts_with("# Invoice INV-0042 (draft)\n"));
+        assert!(markdown.contains("| 2025-08-25 | acme | 3.33 | 90.00 | 300.00 |"));
+        assert!(markdown.contains("| GST (5%) | CAD 27.75 |"));
+        assert!(markdown.contains("| **Total** | **CAD 582.75** |"));
+
+        let html = render_html(&invoice, DurationFormat::default(), true);
+        assert!(html.contains("Acme &lt;Corp&gt;"));
+        assert!(html.contains("@page { size: A4"));
+        assert!(!render_html(&invoice, DurationFormat::default(), false).contains("@page"));
+    }
+
+    #[test]
+    fn test_format_money() {
+        assert_eq!(format_money(5), "0.05");
+        assert_eq!(format_money(123_456), "1,234.56");
+        assert_eq!(format_money(100_000_000), "1,000,000.00");
+        assert_eq!(format_hours(90), "1.50");
+        assert_eq!(format_hours(200), "3.33");
+    }
+
+    #[test]
+    fn test_invoice_sequence() {
+        let directory = std::env::temp_dir().join(format!("markdown_timesheet_invoice_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+
+        assert_eq!(last_invoice_number(&directory).unwrap(), 0);
+        store_invoice_number(&directory, 7).unwrap();
+        assert_eq!(last_invoice_number(&directory).unwrap(), 7);
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,146 @@
+use crate::config::LimitsConfig;
+use crate::duration::DurationFormat;
+use crate::{DaySummary, Diagnostic, DiagnosticKind};
+use chrono::{Duration, NaiveDateTime, NaiveTime};
+
+/// An entry that breaks one of the configured plausibility limits. Its time
+/// still counts towards the totals; the day is flagged so it gets a look.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Implausible {
+    LongSession { start: NaiveTime, end: NaiveTime, duration: Duration, limit: Duration },
+    LongDay { total: Duration, limit: Duration },
+    EarlyStart { start: NaiveTime, limit: NaiveTime },
+    LateStart { start: NaiveTime, limit: NaiveTime },
+    /// A start or stop later than the current time in today's file
+    FutureTime { time: NaiveTime },
+    FutureFile,
+}
+
+impl Implausible {
+    /// The problem in words, with durations in the given format
+    pub fn describe(&self, durations: DurationFormat) -> String {
+        let time = |time: &NaiveTime| time.format("%H:%M").to_string();
+        match self {
+            Self::LongSession { start, end, duration, limit } => format!(
+                "session from {} to {} lasts {}, more than the {} limit",
+                time(start),
+                time(end),
+                durations.format(*duration),
+                durations.format(*limit)
+            ),
+            Self::LongDay { total, limit } => {
+                format!("day totals {}, more than the {} limit", durations.format(*total), durations.format(*limit))
+            }
+            Self::EarlyStart { start, limit } => format!("session starts at {}, before {}", time(start), time(limit)),
+            Self::LateStart { start, limit } => format!("session starts at {}, after {}", time(start), time(limit)),
+            Self::FutureTime { time: at } => format!("{} is later than the current time", time(at)),
+            Self::FutureFile => "file is dated in the future".to_string(),
+        }
+    }
+}
+
+/// Problems with the entries of one day, by line
+fn check_day(day: &DaySummary, limits: &LimitsConfig, now: NaiveDateTime) -> Vec<Diagnostic> {
+    let mut found = Vec::new();
+    let mut push = |line, problem| found.push(Diagnostic { line, kind: DiagnosticKind::Implausible(problem) });
+    let first_line = day.entries.iter().map(|entry| entry.line).chain(day.work_time_entries.iter().map(|entry| entry.line)).min();
+
+    if let Some(first_line) = first_line {
+        let worked = day.total_duration - day.leave_duration;
+        if let Some(limit) = limits.max_day().filter(|limit| worked > *limit) {
+            push(first_line, Implausible::LongDay { total: worked, limit });
+        }
+        if day.date > now.date() {
+            push(first_line, Implausible::FutureFile);
+        }
+    }
+
+    for entry in &day.entries {
+        let Some(start) = entry.start_time else {
+            continue;
+        };
+        if let (Some(end), Some(duration), Some(limit)) = (entry.end_time, entry.duration(), limits.max_session()) {
+            if duration > limit {
+                push(entry.line, Implausible::LongSession { start, end, duration, limit });
+            }
+        }
+        if let Some(limit) = limits.earliest_start.filter(|limit| start < *limit) {
+            push(entry.line, Implausible::EarlyStart { start, limit });
+        }
+        if let Some(limit) = limits.latest_start.filter(|limit| start > *limit) {
+            push(entry.line, Implausible::LateStart { start, limit });
+        }
+        if day.date == now.date() {
+            let stop = entry.end_time.filter(|_| !entry.tentative);
+            if let Some(time) = [Some(start), stop].into_iter().flatten().find(|time| *time > now.time()) {
+                push(entry.line, Implausible::FutureTime { time });
+            }
+        }
+    }
+    found
+}
+
+/// Add a diagnostic for every entry outside the configured limits, keeping
+/// each day's diagnostics in line order
+pub fn apply(summaries: &mut [DaySummary], limits: &LimitsConfig, now: NaiveDateTime) {
+    for day in summaries {
+        let found = check_day(day, limits, now);
+        if !found.is_empty() {
+            day.diagnostics.extend(found);
+            day.diagnostics.sort_by_key(|diagnostic| diagnostic.line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TimesheetParser;
+    use chrono::NaiveDate;
+
+    fn check(date: NaiveDate, content: &str, limits: &LimitsConfig) -> Vec<(usize, String)> {
+        let mut days = [TimesheetParser::new(false).unwrap().parse_file(content, date).unwrap()];
+        let now = NaiveDate::from_ymd_opt(2025, 9, 1).unwrap().and_hms_opt(12, 0, 0).unwrap();
+        apply(&mut days, limits, now);
+        days[0].diagnostics.iter().map(|diagnostic| (diagnostic.line, diagnostic.kind.describe(DurationFormat::default()))).collect()
+    }
+
+    #[test]
+    fn test_long_sessions_and_days() {
+        let date = NaiveDate::from_ymd_opt(2025, 8, 29).unwrap();
+        let found = check(date, "Start work 13:00\nStop work 2:00\nWork time 4 hours", &LimitsConfig::default());
+        assert_eq!(found, [
+            (1, "day totals 17h 00m, more than the 16h 00m limit".to_string()),
+            (1, "session from 13:00 to 02:00 lasts 13h 00m, more than the 12h 00m limit".to_string()),
+        ]);
+
+        let limits = LimitsConfig { max_session: 0.0, max_day: 0.0, ..LimitsConfig::default() };
+        assert!(check(date, "Start work 13:00\nStop work 2:00", &limits).is_empty());
+    }
+
+    #[test]
+    fn test_start_limits() {
+        let date = NaiveDate::from_ymd_opt(2025, 8, 29).unwrap();
+        let limits = LimitsConfig {
+            earliest_start: NaiveTime::from_hms_opt(6, 0, 0),
+            latest_start: NaiveTime::from_hms_opt(22, 0, 0),
+            ..LimitsConfig::default()
+        };
+        let found = check(date, "Start work 5:00\nStop work 6:00\nStart work 23:00\nStop work 23:30", &limits);
+        assert_eq!(found, [
+            (1, "session starts at 05:00, before 06:00".to_string()),
+            (3, "session starts at 23:00, after 22:00".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_future_entries() {
+        let today = NaiveDate::from_ymd_opt(2025, 9, 1).unwrap();
+        let found = check(today, "Start work 9:00\nStop work 13:00", &LimitsConfig::default());
+        assert_eq!(found, [(1, "13:00 is later than the current time".to_string())]);
+
+        let found = check(today + Duration::days(1), "# Plan\nWork time 1 hour", &LimitsConfig::default());
+        assert_eq!(found, [(2, "file is dated in the future".to_string())]);
+        assert!(check(today + Duration::days(1), "# Plan", &LimitsConfig::default()).is_empty());
+    }
+}
@@ -1,15 +1,55 @@
-use chrono::{Datelike, Duration, Local, NaiveDate, NaiveTime};
+mod activity;
+mod chart;
+mod cli;
+mod clock;
+mod compliance;
+mod config;
+mod coverage;
+mod csv_import;
+mod diff;
+mod duration;
+mod error;
+mod export;
+mod fix;
+mod fmt;
+mod holidays;
+mod ics;
+mod import;
+mod invoice;
+mod limits;
+mod report;
+mod rounding;
+mod serve;
+mod timewarrior;
+mod tui;
+
+use chrono::{Datelike, Duration, Local, Months, NaiveDate, NaiveTime, Weekday};
+use clap::{CommandFactory, Parser};
+use cli::{Cli, Command, CommonArgs, CurrentPeriod, ExportFormat, ImportSource, WeekArgs};
+use config::{Config, ScheduleConfig};
+use coverage::CoverageIssue;
+use duration::DurationFormat;
+use error::TimesheetError;
+use holidays::Holiday;
 use regex::Regex;
+use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::env;
 use std::fs;
-use std::path::Path;
+use std::io;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::thread;
 
 #[derive(Debug, Clone, PartialEq)]
 struct TimeEntry {
     start_time: Option<NaiveTime>,
     end_time: Option<NaiveTime>,
     tentative: bool,
+    line: usize,
+    tags: Vec<String>,
+    /// Text after the start and stop times, followed by any notes written under the start line
+    description: String,
 }
 
 impl TimeEntry {
@@ -18,9 +58,17 @@ impl TimeEntry {
             start_time: None,
             end_time: None,
             tentative: false,
+            line: 0,
+            tags: Vec::new(),
+            description: String::new(),
         }
     }
 
+    /// The first `#tag` on the entry's start or stop line
+    fn project(&self) -> Option<&str> {
+        self.tags.first().map(String::as_str)
+    }
+
     fn duration(&self) -> Option<Duration> {
         match (self.start_time, self.end_time) {
             (Some(start), Some(end)) => {
@@ -35,12 +83,96 @@ impl TimeEntry {
     }
 }
 
-#[derive(Debug, Clone)]
+/// A "Work time" entry: an amount of time logged without start or stop times
+#[derive(Debug, Clone, PartialEq)]
+struct WorkTimeEntry {
+    duration: Duration,
+    line: usize,
+    tags: Vec<String>,
+    /// Text after the amount, e.g. "code review", followed by any notes under the line
+    description: String,
+}
+
+/// The entry that free-text lines are currently being attached to
+#[derive(Debug, Clone, Copy)]
+enum NotesTarget {
+    OpenSession,
+    WorkTime(usize),
+}
+
+/// Add a piece of text to an entry description, separating pieces with "; "
+fn append_description(description: &mut String, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    if !description.is_empty() {
+        description.push_str("; ");
+    }
+    description.push_str(text);
+}
+
+/// Add the tags an entry does not have yet, keeping their order
+fn merge_tags(tags: &mut Vec<String>, new: Vec<String>) {
+    for tag in new {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+}
+
+impl WorkTimeEntry {
+    fn project(&self) -> Option<&str> {
+        self.tags.first().map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum DiagnosticKind {
+    OpenSession { start: NaiveTime },
+    OrphanedStop { stop: NaiveTime },
+    InvalidTime { text: String },
+    Implausible(limits::Implausible),
+}
+
+/// A problem found on a specific line of a timesheet file
+#[derive(Debug, Clone, PartialEq)]
+struct Diagnostic {
+    line: usize,
+    kind: DiagnosticKind,
+}
+
+impl DiagnosticKind {
+    /// The problem in words, with durations in the given format
+    fn describe(&self, durations: DurationFormat) -> String {
+        match self {
+            Self::OpenSession { start } => format!("session started at {} has no matching stop", start.format("%H:%M")),
+            Self::OrphanedStop { stop } => format!("stop at {} has no matching start", stop.format("%H:%M")),
+            Self::InvalidTime { text } => format!("invalid time in \"{text}\""),
+            Self::Implausible(problem) => problem.describe(durations),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 struct DaySummary {
     date: NaiveDate,
     total_duration: Duration,
+    /// Portion of `total_duration` credited by holiday and PTO entries
+    leave_duration: Duration,
     has_tentative: bool,
     has_incomplete: bool,
+    entries: Vec<TimeEntry>,
+    work_time_entries: Vec<WorkTimeEntry>,
+    diagnostics: Vec<Diagnostic>,
+    /// Name of the public holiday from the configured holiday file
+    holiday: Option<String>,
+}
+
+impl DaySummary {
+    /// Whether an entry breaks one of the configured plausibility limits
+    fn is_implausible(&self) -> bool {
+        self.diagnostics.iter().any(|diagnostic| matches!(diagnostic.kind, DiagnosticKind::Implausible(_)))
+    }
 }
 
 #[derive(Debug)]
@@ -50,6 +182,27 @@ struct WeekSummary {
     days: Vec<DaySummary>,
 }
 
+impl WeekSummary {
+    fn week_end(&self) -> NaiveDate {
+        self.week_start + Duration::days(6)
+    }
+
+    fn label(&self, iso_weeks: bool) -> String {
+        if iso_weeks {
+            iso_week_label(self.week_start)
+        } else {
+            format!("Week of {} - {}", self.week_start, self.week_end())
+        }
+    }
+}
+
+/// ISO 8601 week label such as "2025-W35". Weeks that don't start on a
+/// Monday take the ISO week they share at least four days with.
+fn iso_week_label(week_start: NaiveDate) -> String {
+    let iso_week = (week_start + Duration::days(3)).iso_week();
+    format!("{}-W{:02}", iso_week.year(), iso_week.week())
+}
+
 #[derive(Debug)]
 struct MonthlySummary {
     year: i32,
@@ -57,21 +210,89 @@ struct MonthlySummary {
     total_duration: Duration,
 }
 
+/// Totals for a span of days longer than a month, such as a quarter or a year
+#[derive(Debug)]
+struct PeriodSummary {
+    label: String,
+    start: NaiveDate,
+    end: NaiveDate,
+    total_duration: Duration,
+    leave_duration: Duration,
+    leave_days: usize,
+    worked_days: usize,
+}
+
+impl PeriodSummary {
+    /// Expected hours for the scheduled workdays of this period that have been tracked so far
+    fn expected_duration(&self, schedule: &ScheduleConfig, weekly_hours: f64, tracking_start: NaiveDate, today: NaiveDate) -> Duration {
+        let first = self.start.max(tracking_start);
+        let last = self.end.min(today);
+        let workdays = first
+            .iter_days()
+            .take_while(|date| *date <= last)
+            .filter(|date| schedule.is_workday(*date))
+            .count();
+
+        schedule.daily_target(hours_to_duration(weekly_hours)) * i32::try_from(workdays).unwrap_or(i32::MAX)
+    }
+
+    /// Average hours per day with logged work, leaving out holiday and PTO credit
+    fn average_worked_day(&self) -> Option<Duration> {
+        let worked_days = i32::try_from(self.worked_days).ok().filter(|days| *days > 0)?;
+        Some((self.total_duration - self.leave_duration) / worked_days)
+    }
+}
+
+/// How pay periods are laid out on the calendar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PayPeriod {
+    /// Fourteen-day periods, one of which starts on the anchor date
+    Biweekly { anchor: NaiveDate },
+    /// The 1st to the 15th and the 16th to the end of each month
+    SemiMonthly,
+}
+
+impl PayPeriod {
+    /// First and last day of the pay period containing `date`
+    fn bounds(self, date: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+        match self {
+            Self::Biweekly { anchor } => {
+                let offset = (date - anchor).num_days().div_euclid(14) * 14;
+                let start = anchor + Duration::days(offset);
+                Some((start, start + Duration::days(13)))
+            }
+            Self::SemiMonthly if date.day() <= 15 => Some((date.with_day(1)?, date.with_day(15)?)),
+            Self::SemiMonthly => {
+                let end = date.with_day(1)?.checked_add_months(Months::new(1))?.pred_opt()?;
+                Some((date.with_day(16)?, end))
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct DirectoryScan {
+    summaries: Vec<DaySummary>,
+    errors: Vec<TimesheetError>,
+}
+
 struct TimesheetParser {
     start_regex: Regex,
     stop_regex: Regex,
     work_time_regex: Regex,
     holiday_regex: Regex,
+    tag_regex: Regex,
     debug_mode: bool,
 }
 
 impl TimesheetParser {
-    fn new(debug_mode: bool) -> Result<Self, regex::Error> {
+    fn new(debug_mode: bool) -> Result<Self, TimesheetError> {
         Ok(Self {
             start_regex: Regex::new(r"(?i)start(?:ed)?\s+work(?:ing)?(?:\s+at)?\s+(\d{1,2}):(\d{2})")?,
             stop_regex: Regex::new(r"(?i)stop(?:ped)?\s+work(?:ing)?(?:\s+at)?\s+(\d{1,2}):(\d{2})")?,
             work_time_regex: Regex::new(r"(?i)work\s+time\s+(\d+)\s+(minutes?|hours?)")?,
             holiday_regex: Regex::new(r"(?i)(stat(?:utory)?\s+holiday|pto|holiday\s+day)")?,
+            tag_regex: Regex::new(r"(?:^|\s)#([A-Za-z][\w-]*)")?,
             debug_mode,
         })
     }
@@ -82,10 +303,9 @@ impl TimesheetParser {
 
         if is_today && !entries.is_empty() {
             let last_entry = entries.last_mut().unwrap();
-            if last_entry.start_time.is_some() && last_entry.end_time.is_none() {
+            if let (Some(start_time), None) = (last_entry.start_time, last_entry.end_time) {
                 let current_time = Local::now().time();
-                let start_time = last_entry.start_time.unwrap();
-                
+
                 if self.debug_mode {
                     eprintln!("DEBUG: Applying tentative time to last incomplete entry");
                     eprintln!("DEBUG: Start time: {start_time}, Current time: {current_time}");
@@ -135,14 +355,58 @@ impl TimesheetParser {
         (has_tentative, has_incomplete)
     }
 
+    /// Whether the line is an entry of any kind
+    fn is_entry(&self, line: &str) -> bool {
+        [&self.start_regex, &self.stop_regex, &self.work_time_regex, &self.holiday_regex]
+            .iter()
+            .any(|regex| regex.is_match(line))
+    }
+
+    /// `#project` style tags on an entry line, in order of appearance
+    fn parse_tags(&self, line: &str) -> Vec<String> {
+        self.tag_regex
+            .captures_iter(line)
+            .map(|caps| caps[1].to_lowercase())
+            .collect()
+    }
+
+    /// Free text on an entry line after the matched entry, without its tags
+    fn trailing_text(&self, line: &str, entry_end: usize) -> String {
+        let text = self.tag_regex.replace_all(&line[entry_end..], " ");
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// A prose line under an entry, without list markers; headings and blank lines are not notes.
+    /// A line starting with a tag, such as "#acme follow-up", is a note.
+    fn note_text(line: &str) -> Option<&str> {
+        let trimmed = line.trim();
+        let is_heading = trimmed.strip_prefix('#').is_some_and(|rest| rest.is_empty() || rest.starts_with(['#', ' ', '\t']));
+        if trimmed.is_empty() || is_heading {
+            return None;
+        }
+        let text = trimmed.trim_start_matches(['-', '*', '+', '>']).trim_start();
+        Some(text).filter(|text| !text.is_empty())
+    }
+
+    fn parse_number(value: &str, line_num: usize, line: &str) -> Result<u32, TimesheetError> {
+        value.parse().map_err(|error| {
+            let trimmed_line = line.trim();
+            TimesheetError::parse(line_num, format!("invalid number \"{value}\" in \"{trimmed_line}\": {error}"))
+        })
+    }
+
     #[allow(clippy::too_many_lines)]
-    fn parse_file(&self, content: &str, date: NaiveDate) -> Result<DaySummary, Box<dyn std::error::Error>> {
+    fn parse_file(&self, content: &str, date: NaiveDate) -> Result<DaySummary, TimesheetError> {
         let mut entries = Vec::new();
+        let mut work_time_entries = Vec::new();
         let mut current_entry = TimeEntry::new();
         let mut total_work_time_duration = Duration::zero();
+        let mut leave_duration = Duration::zero();
         let today = Local::now().date_naive();
         let is_today = date == today;
         let mut has_orphaned_stop = false;
+        let mut diagnostics = Vec::new();
+        let mut notes_target = None;
 
         if self.debug_mode {
             eprintln!("DEBUG: Parsing file for date: {date}");
@@ -163,26 +427,40 @@ impl TimesheetParser {
                     current_entry = TimeEntry::new();
                 }
 
-                let hours: u32 = caps[1].parse()?;
-                let minutes: u32 = caps[2].parse()?;
+                let hours = Self::parse_number(&caps[1], line_num, line)?;
+                let minutes = Self::parse_number(&caps[2], line_num, line)?;
                 
                 if let Some(time) = NaiveTime::from_hms_opt(hours, minutes, 0) {
                     current_entry.start_time = Some(time);
+                    current_entry.line = line_num;
+                    current_entry.tags = self.parse_tags(line);
+                    current_entry.description = self.trailing_text(line, caps.get(0).map_or(0, |m| m.end()));
+                    notes_target = Some(NotesTarget::OpenSession);
                     if self.debug_mode {
                         let trimmed_line = line.trim();
                         eprintln!("DEBUG: Line {line_num}: Found start work at {time} (\"{trimmed_line}\")");
                     }
-                } else if self.debug_mode {
-                    eprintln!("DEBUG: Line {line_num}: Invalid time format {hours}:{minutes:02} in start work entry");
+                } else {
+                    if self.debug_mode {
+                        eprintln!("DEBUG: Line {line_num}: Invalid time format {hours}:{minutes:02} in start work entry");
+                    }
+                    diagnostics.push(Diagnostic {
+                        line: line_num,
+                        kind: DiagnosticKind::InvalidTime { text: caps[0].to_string() },
+                    });
                 }
             } else if let Some(caps) = self.stop_regex.captures(line) {
-                let hours: u32 = caps[1].parse()?;
-                let minutes: u32 = caps[2].parse()?;
+                let hours = Self::parse_number(&caps[1], line_num, line)?;
+                let minutes = Self::parse_number(&caps[2], line_num, line)?;
                 
                 if let Some(time) = NaiveTime::from_hms_opt(hours, minutes, 0) {
                     if current_entry.start_time.is_some() {
                         // Normal case: stop time for existing start time
                         current_entry.end_time = Some(time);
+                        let trailing = self.trailing_text(line, caps.get(0).map_or(0, |m| m.end()));
+                        append_description(&mut current_entry.description, &trailing);
+                        notes_target = None;
+                        merge_tags(&mut current_entry.tags, self.parse_tags(line));
                         if self.debug_mode {
                             let duration = current_entry.duration().unwrap_or(Duration::zero());
                             let trimmed_line = line.trim();
@@ -193,16 +471,26 @@ impl TimesheetParser {
                     } else {
                         // Error case: stop time without start time
                         has_orphaned_stop = true;
+                        diagnostics.push(Diagnostic {
+                            line: line_num,
+                            kind: DiagnosticKind::OrphanedStop { stop: time },
+                        });
                         if self.debug_mode {
                             let trimmed_line = line.trim();
                             eprintln!("ERROR: Line {line_num}: Found stop work at {time} without corresponding start work (\"{trimmed_line}\")");
                         }
                     }
-                } else if self.debug_mode {
-                    eprintln!("DEBUG: Line {line_num}: Invalid time format {hours}:{minutes:02} in stop work entry");
+                } else {
+                    if self.debug_mode {
+                        eprintln!("DEBUG: Line {line_num}: Invalid time format {hours}:{minutes:02} in stop work entry");
+                    }
+                    diagnostics.push(Diagnostic {
+                        line: line_num,
+                        kind: DiagnosticKind::InvalidTime { text: caps[0].to_string() },
+                    });
                 }
             } else if let Some(caps) = self.work_time_regex.captures(line) {
-                let amount: u32 = caps[1].parse()?;
+                let amount = Self::parse_number(&caps[1], line_num, line)?;
                 let unit = caps[2].to_lowercase();
                 
                 let duration = if unit.starts_with("hour") {
@@ -218,12 +506,34 @@ impl TimesheetParser {
                     eprintln!("DEBUG: Line {line_num}: Found work time {amount} {unit} (duration: {duration:?}) (\"{trimmed_line}\")");
                 }
                 total_work_time_duration += duration;
+                work_time_entries.push(WorkTimeEntry {
+                    duration,
+                    line: line_num,
+                    tags: self.parse_tags(line),
+                    description: self.trailing_text(line, caps.get(0).map_or(0, |m| m.end())),
+                });
+                notes_target = Some(NotesTarget::WorkTime(work_time_entries.len() - 1));
             } else if self.holiday_regex.is_match(line) {
+                notes_target = None;
                 if self.debug_mode {
                     let trimmed_line = line.trim();
                     eprintln!("DEBUG: Line {line_num}: Found holiday entry (8h 00m) (\"{trimmed_line}\")");
                 }
                 total_work_time_duration += Duration::hours(8);
+                leave_duration += Duration::hours(8);
+            } else if let Some(note) = Self::note_text(line) {
+                let target = match notes_target {
+                    Some(NotesTarget::OpenSession) => Some((&mut current_entry.description, &mut current_entry.tags)),
+                    Some(NotesTarget::WorkTime(index)) => {
+                        let entry = &mut work_time_entries[index];
+                        Some((&mut entry.description, &mut entry.tags))
+                    }
+                    None => None,
+                };
+                if let Some((description, tags)) = target {
+                    append_description(description, &self.trailing_text(note, 0));
+                    merge_tags(tags, self.parse_tags(note));
+                }
             }
         }
 
@@ -247,6 +557,14 @@ impl TimesheetParser {
         let total_duration = time_entries_duration + total_work_time_duration;
         let (has_tentative, has_incomplete) = Self::calculate_flags(&entries, has_orphaned_stop, date);
 
+        diagnostics.extend(entries.iter().filter(|entry| entry.end_time.is_none()).filter_map(|entry| {
+            entry.start_time.map(|start| Diagnostic {
+                line: entry.line,
+                kind: DiagnosticKind::OpenSession { start },
+            })
+        }));
+        diagnostics.sort_by_key(|diagnostic| diagnostic.line);
+
         if self.debug_mode {
             eprintln!("DEBUG: Parsing complete for {date}");
             let entries_len = entries.len();
@@ -271,38 +589,93 @@ impl TimesheetParser {
         Ok(DaySummary {
             date,
             total_duration,
+            leave_duration,
             has_tentative,
             has_incomplete,
+            entries,
+            work_time_entries,
+            diagnostics,
+            holiday: None,
         })
     }
 
-    fn parse_directory(&self, dir_path: &Path) -> Result<Vec<DaySummary>, Box<dyn std::error::Error>> {
+    fn parse_directory(&self, dir_path: &Path) -> Result<DirectoryScan, TimesheetError> {
+        let files = Self::collect_timesheet_files(dir_path)?;
+
+        // Debug output is only readable when files are parsed one after another
+        let workers = if self.debug_mode {
+            1
+        } else {
+            thread::available_parallelism().map_or(1, NonZeroUsize::get).min(files.len()).max(1)
+        };
+        let chunk_size = files.len().div_ceil(workers).max(1);
+
+        let results: Vec<_> = thread::scope(|scope| {
+            let handles: Vec<_> = files
+                .chunks(chunk_size)
+                .map(|chunk| scope.spawn(move || self.parse_files(chunk)))
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+                .collect()
+        });
+
         let mut summaries = Vec::new();
+        let mut errors = Vec::new();
+        for result in results {
+            match result {
+                Ok(summary) => summaries.push(summary),
+                Err(error) => {
+                    if self.debug_mode {
+                        eprintln!("ERROR: Skipping {error}");
+                    }
+                    errors.push(error);
+                }
+            }
+        }
 
-        for entry in fs::read_dir(dir_path)? {
-            let entry = entry?;
+        summaries.sort_unstable_by_key(|summary| summary.date);
+        Ok(DirectoryScan { summaries, errors })
+    }
+
+    fn collect_timesheet_files(dir_path: &Path) -> Result<Vec<(NaiveDate, PathBuf)>, TimesheetError> {
+        let mut files = Vec::new();
+
+        let read_error = |error| TimesheetError::io(dir_path, error);
+        for entry in fs::read_dir(dir_path).map_err(read_error)? {
+            let entry = entry.map_err(read_error)?;
             let path = entry.path();
 
             if path.extension().is_some_and(|ext| ext == "md") {
                 if let Some(filename) = path.file_stem().and_then(|s| s.to_str()) {
                     if let Ok(date) = NaiveDate::parse_from_str(filename, "%Y-%m-%d") {
-                        let content = fs::read_to_string(&path)?;
-                        let summary = self.parse_file(&content, date)?;
-                        summaries.push(summary);
+                        files.push((date, path));
                     }
                 }
             }
         }
 
-        summaries.sort_unstable_by_key(|summary| summary.date);
-        Ok(summaries)
+        files.sort_unstable();
+        Ok(files)
     }
 
-    fn group_by_week(summaries: &[DaySummary]) -> Vec<WeekSummary> {
+    fn parse_files(&self, files: &[(NaiveDate, PathBuf)]) -> Vec<Result<DaySummary, TimesheetError>> {
+        files
+            .iter()
+            .map(|(date, path)| {
+                let content = fs::read_to_string(path).map_err(|error| TimesheetError::io(path, error))?;
+                self.parse_file(&content, *date).map_err(|error| error.with_path(path))
+            })
+            .collect()
+    }
+
+    fn group_by_week(summaries: &[DaySummary], first_day: Weekday) -> Vec<WeekSummary> {
         let mut weeks: HashMap<NaiveDate, Vec<DaySummary>> = HashMap::new();
 
         for summary in summaries {
-            let week_start = summary.date - Duration::days(i64::from(summary.date.weekday().num_days_from_monday()));
+            let week_start = summary.date.week(first_day).first_day();
             weeks.entry(week_start).or_default().push(summary.clone());
         }
 
@@ -347,20 +720,73 @@ impl TimesheetParser {
         monthly_summaries.sort_unstable_by_key(|summary| (summary.year, summary.month));
         monthly_summaries
     }
-}
 
-fn format_duration(duration: Duration) -> String {
-    let total_minutes = duration.num_minutes();
-    let hours = total_minutes / 60;
-    let minutes = total_minutes % 60;
-    format!("{hours}h {minutes:02}m")
+    fn group_by_quarter(summaries: &[DaySummary]) -> Vec<PeriodSummary> {
+        Self::group_by_period(summaries, |date| {
+            let quarter = date.month0() / 3;
+            let start = NaiveDate::from_ymd_opt(date.year(), quarter * 3 + 1, 1)?;
+            let end = start.checked_add_months(Months::new(3))?.pred_opt()?;
+            Some((format!("Q{} {}", quarter + 1, date.year()), start, end))
+        })
+    }
+
+    fn group_by_year(summaries: &[DaySummary]) -> Vec<PeriodSummary> {
+        Self::group_by_period(summaries, |date| {
+            let start = NaiveDate::from_ymd_opt(date.year(), 1, 1)?;
+            let end = NaiveDate::from_ymd_opt(date.year(), 12, 31)?;
+            Some((date.year().to_string(), start, end))
+        })
+    }
+
+    fn group_by_pay_period(summaries: &[DaySummary], pay_period: PayPeriod) -> Vec<PeriodSummary> {
+        Self::group_by_period(summaries, |date| {
+            let (start, end) = pay_period.bounds(date)?;
+            Some((format!("{start} - {end}"), start, end))
+        })
+    }
+
+    /// Group days into the period returned by `period_of`, given as (label, first day, last day)
+    fn group_by_period<F>(summaries: &[DaySummary], period_of: F) -> Vec<PeriodSummary>
+    where
+        F: Fn(NaiveDate) -> Option<(String, NaiveDate, NaiveDate)>,
+    {
+        let mut periods: HashMap<NaiveDate, PeriodSummary> = HashMap::new();
+
+        for summary in summaries {
+            let Some((label, start, end)) = period_of(summary.date) else {
+                continue;
+            };
+            let period = periods.entry(start).or_insert_with(|| PeriodSummary {
+                label,
+                start,
+                end,
+                total_duration: Duration::zero(),
+                leave_duration: Duration::zero(),
+                leave_days: 0,
+                worked_days: 0,
+            });
+
+            period.total_duration += summary.total_duration;
+            period.leave_duration += summary.leave_duration;
+            if summary.leave_duration > Duration::zero() {
+                period.leave_days += 1;
+            }
+            if summary.total_duration > summary.leave_duration {
+                period.worked_days += 1;
+            }
+        }
+
+        let mut period_summaries: Vec<_> = periods.into_values().collect();
+        period_summaries.sort_unstable_by_key(|period| period.start);
+        period_summaries
+    }
 }
 
-fn format_duration_with_flags(duration: Duration, has_tentative: bool, has_incomplete: bool) -> String {
-    let total_minutes = duration.num_minutes();
-    let hours = total_minutes / 60;
-    let minutes = total_minutes % 60;
-    
+/// A duration followed by "*" for tentative time, "E!" for incomplete entries
+/// and "L!" for entries outside the plausibility limits
+fn format_duration_with_flags(durations: DurationFormat, duration: Duration, has_tentative: bool, has_incomplete: bool, is_implausible: bool) -> String {
+    let formatted = durations.format(duration);
+
     let mut flags = String::new();
     if has_tentative {
         flags.push('*');
@@ -371,11 +797,17 @@ fn format_duration_with_flags(duration: Duration, has_tentative: bool, has_incom
         }
         flags.push_str("E!");
     }
+    if is_implausible {
+        if !flags.is_empty() {
+            flags.push(' ');
+        }
+        flags.push_str("L!");
+    }
     
     if flags.is_empty() {
-        format!("{hours}h {minutes:02}m")
+        formatted
     } else {
-        format!("{hours}h {minutes:02}m {flags}")
+        format!("{formatted} {flags}")
     }
 }
 
@@ -388,168 +820,266 @@ fn get_month_name(month: u32) -> &'static str {
     MONTH_NAMES.get(month.saturating_sub(1) as usize).map_or("Unknown", |&name| name)
 }
 
-fn print_status_bar_summary(summaries: &[DaySummary], weeks: &[WeekSummary], weekly_hours: f64) {
+fn print_status_bar_summary(summaries: &[DaySummary], durations: DurationFormat, period_str: &str, warning_count: usize) {
     let today = chrono::Local::now().date_naive();
     
     // Find today's summary
     let today_summary = summaries.iter().find(|s| s.date == today);
     
-    // Find current week's summary
-    let current_week = weeks.iter().find(|week| {
-        let week_end = week.week_start + Duration::days(6);
-        today >= week.week_start && today <= week_end
-    });
-    
-    match (today_summary, current_week) {
-        (Some(day), Some(week)) => {
-            let day_str = format_duration_with_flags(day.total_duration, day.has_tentative, day.has_incomplete);
-            let week_str = format_duration(week.total_duration);
-            
-            #[allow(clippy::cast_precision_loss)]
-            let week_hours = week.total_duration.num_minutes() as f64 / 60.0;
-            let week_status = if week_hours < weekly_hours {
-                let shortage = weekly_hours - week_hours;
-                format!(" ({shortage:.1}h short)")
-            } else {
-                String::new()
-            };
-            
-            println!("Today: {day_str} | Week: {week_str}{week_status}");
-        }
-        (Some(day), None) => {
-            let day_str = format_duration_with_flags(day.total_duration, day.has_tentative, day.has_incomplete);
-            println!("Today: {day_str} | Week: No data");
-        }
-        (None, Some(week)) => {
-            let week_str = format_duration(week.total_duration);
-            #[allow(clippy::cast_precision_loss)]
-            let week_hours = week.total_duration.num_minutes() as f64 / 60.0;
-            let week_status = if week_hours < weekly_hours {
-                let shortage = weekly_hours - week_hours;
-                format!(" ({shortage:.1}h short)")
-            } else {
-                String::new()
-            };
-            println!("Today: No data | Week: {week_str}{week_status}");
-        }
-        (None, None) => {
-            println!("Today: No data | Week: No data");
-        }
+    let day_str = today_summary.map_or_else(
+        || "No data".to_string(),
+        |day| format_duration_with_flags(durations, day.total_duration, day.has_tentative, day.has_incomplete, day.is_implausible()),
+    );
+
+    if warning_count > 0 {
+        println!("Today: {day_str} | {period_str} | W!");
+    } else {
+        println!("Today: {day_str} | {period_str}");
     }
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = env::args().collect();
-    
-    let mut directory = ".";
-    let mut weekly_hours = 40.0;
-    let mut debug_mode = false;
-    let mut summarize_mode = false;
-    
-    let mut i = 1;
-    while i < args.len() {
-        match args[i].as_str() {
-            "--weekly-hours" => {
-                if let Some(value) = args.get(i + 1) {
-                    weekly_hours = value.parse().unwrap_or(40.0);
-                    i += 2;
-                } else {
-                    eprintln!("Error: --weekly-hours requires a value");
-                    return Ok(());
-                }
-            }
-            "--debug" => {
-                debug_mode = true;
-                i += 1;
-            }
-            "--summarize" => {
-                summarize_mode = true;
-                i += 1;
-            }
-            "--help" | "-h" => {
-                println!("Usage: {} [directory] [--weekly-hours HOURS] [--debug] [--summarize]", args[0]);
-                println!("  directory: Directory containing markdown timesheet files (default: current directory)");
-                println!("  --weekly-hours: Expected weekly work hours (default: 40)");
-                println!("  --debug: Show detailed debug information and error locations");
-                println!("  --summarize: Show compact current day and week summary for status bar");
-                return Ok(());
-            }
-            _ => {
-                directory = &args[i];
-                i += 1;
-            }
-        }
+fn format_shortage(durations: DurationFormat, actual: Duration, expected: Duration) -> String {
+    if actual < expected {
+        format!("{} ({} short)", durations.format(actual), durations.format(expected - actual))
+    } else {
+        durations.format(actual)
     }
+}
 
-    let parser = TimesheetParser::new(debug_mode)?;
-    let summaries = parser.parse_directory(Path::new(directory))?;
-    let weeks = TimesheetParser::group_by_week(&summaries);
+fn hours_to_duration(hours: f64) -> Duration {
+    #[allow(clippy::cast_possible_truncation)]
+    let minutes = (hours * 60.0).round() as i64;
+    Duration::minutes(minutes)
+}
 
-    if summarize_mode {
-        print_status_bar_summary(&summaries, &weeks, weekly_hours);
-        return Ok(());
-    }
+/// Status bar segment for the current week, e.g. "Week: 32h 15m (7h 45m short)"
+fn current_week_status(summaries: &[DaySummary], durations: DurationFormat, weekly_hours: f64, week_options: &WeekArgs, today: NaiveDate) -> String {
+    let current_week_start = today.week(week_options.week_start).first_day();
+    let weeks = TimesheetParser::group_by_week(summaries, week_options.week_start);
+    let current_week = weeks.iter().find(|week| week.week_start == current_week_start);
 
-    let months = TimesheetParser::group_by_month(&summaries);
+    let week_label = if week_options.iso_weeks {
+        iso_week_label(current_week_start)
+    } else {
+        "Week".to_string()
+    };
+    let week_str = current_week.map_or_else(
+        || "No data".to_string(),
+        |week| format_shortage(durations, week.total_duration, hours_to_duration(weekly_hours)),
+    );
+
+    format!("{week_label}: {week_str}")
+}
 
-    // Calculate the date two weeks ago from today
-    let today = chrono::Local::now().date_naive();
-    let two_weeks_ago = today - Duration::days(14);
+/// Status bar segment for the current pay period, measured against the whole period's target
+fn current_pay_period_status(summaries: &[DaySummary], durations: DurationFormat, schedule: &ScheduleConfig, weekly_hours: f64, pay_period: PayPeriod, today: NaiveDate) -> String {
+    let periods = TimesheetParser::group_by_pay_period(summaries, pay_period);
+    let current_period = pay_period
+        .bounds(today)
+        .and_then(|(start, _)| periods.iter().find(|period| period.start == start));
+
+    let period_str = current_period.map_or_else(
+        || "No data".to_string(),
+        |period| {
+            let expected = period.expected_duration(schedule, weekly_hours, period.start, period.end);
+            format_shortage(durations, period.total_duration, expected)
+        },
+    );
+
+    format!("Pay period: {period_str}")
+}
 
-    println!("Daily Summary (Last 2 Weeks):");
-    println!("==============================");
-    weeks
-        .iter()
-        .flat_map(|week| &week.days)
-        .filter(|day| (day.total_duration > Duration::zero() || day.has_incomplete) && day.date >= two_weeks_ago)
-        .for_each(|day| {
-            let weekday = day.date.format("%a");
-            println!("{} {:3} - {}", day.date, weekday, format_duration_with_flags(day.total_duration, day.has_tentative, day.has_incomplete));
-        });
+fn print_warnings(errors: &[TimesheetError]) {
+    let warnings: Vec<_> = errors.iter().map(ToString::to_string).collect();
+    print!("{}", report::render_warnings(&warnings));
+}
 
-    println!("\nMonthly Summary:");
-    println!("================");
-    months
-        .iter()
-        .filter(|month| month.total_duration > Duration::zero())
-        .for_each(|month| {
-            println!("{} {}: {}", get_month_name(month.month), month.year, format_duration(month.total_duration));
-        });
+fn format_balance(durations: DurationFormat, actual: Duration, expected: Duration) -> String {
+    match actual.cmp(&expected) {
+        Ordering::Less => format!(" [{} short]", durations.format(expected - actual)),
+        Ordering::Greater => format!(" [{} over]", durations.format(actual - expected)),
+        Ordering::Equal => String::new(),
+    }
+}
+
+fn print_check(directory: &Path, scan: &DirectoryScan, issues: &[CoverageIssue], durations: DurationFormat) -> ExitCode {
+    for error in &scan.errors {
+        println!("{error}");
+    }
 
-    println!("\nWeekly Summary:");
-    println!("===============");
-    weeks
+    let mut problems: Vec<_> = scan
+        .summaries
         .iter()
-        .filter(|week| week.total_duration > Duration::zero())
-        .for_each(|week| {
-            let week_end = week.week_start + Duration::days(6);
-            #[allow(clippy::cast_precision_loss)]
-            let actual_hours = week.total_duration.num_minutes() as f64 / 60.0;
-            let formatted_duration = format_duration(week.total_duration);
-            
-            if actual_hours < weekly_hours {
-                #[allow(clippy::cast_possible_truncation)]
-                let difference_minutes = ((weekly_hours - actual_hours) * 60.0).round() as i64;
-                let difference_duration = Duration::minutes(difference_minutes);
-                println!(
-                    "Week of {} - {}: {} [{}h {:02}m short]",
-                    week.week_start,
-                    week_end,
-                    formatted_duration,
-                    difference_duration.num_hours(),
-                    difference_duration.num_minutes() % 60
-                );
-            } else {
-                println!(
-                    "Week of {} - {}: {}",
-                    week.week_start,
-                    week_end,
-                    formatted_duration
-                );
+        .flat_map(|day| day.diagnostics.iter().map(move |diagnostic| (day.date, Some(diagnostic.line), diagnostic.kind.describe(durations))))
+        .collect();
+    problems.extend(issues.iter().map(|issue| (issue.date, issue.finding.line(), issue.finding.describe(durations))));
+    problems.sort_by_key(|(date, line, _)| (*date, *line));
+    let problem_count = scan.errors.len() + problems.len();
+
+    for (date, line, message) in problems {
+        let path = directory.join(format!("{date}.md"));
+        match line {
+            Some(line) => println!("{}:{line}: {message}", path.display()),
+            None => println!("{}: {message}", path.display()),
+        }
+    }
+
+    let file_count = scan.summaries.len() + scan.errors.len();
+    if problem_count == 0 {
+        println!("No problems found in {file_count} files");
+        ExitCode::SUCCESS
+    } else {
+        let noun = if problem_count == 1 { "problem" } else { "problems" };
+        println!("{problem_count} {noun} found in {file_count} files");
+        ExitCode::FAILURE
+    }
+}
+
+/// Name of the imported file in error messages
+fn import_path(input: &cli::ImportFileArgs) -> &Path {
+    input.file.as_deref().unwrap_or(Path::new("<stdin>"))
+}
+
+/// Load the configuration and the duration format it sets, with command line overrides
+fn load_config(common: &CommonArgs) -> Result<(Config, DurationFormat), TimesheetError> {
+    let config = Config::load(&common.directory, common.config.as_deref())?;
+    let durations = config.format.resolve(common.duration_style, common.precision)?;
+    Ok((config, durations))
+}
+
+/// Scan the directory and credit the configured public holidays up to today
+fn scan_with_holidays(common: &CommonArgs, config: &Config, today: NaiveDate) -> Result<(DirectoryScan, Vec<Holiday>), TimesheetError> {
+    let mut scan = scan_directory(common)?;
+    let holidays = holidays::load(&common.directory, &config.holidays)?;
+    holidays::apply(&mut scan.summaries, &holidays, &config.schedule, config.holidays.credit()?, today);
+    limits::apply(&mut scan.summaries, &config.limits, Local::now().naive_local());
+    Ok((scan, holidays))
+}
+
+fn scan_directory(common: &CommonArgs) -> Result<DirectoryScan, TimesheetError> {
+    let parser = TimesheetParser::new(common.debug)?;
+    let mut scan = parser.parse_directory(&common.directory)?;
+    if common.strict && !scan.errors.is_empty() {
+        return Err(scan.errors.swap_remove(0));
+    }
+    Ok(scan)
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            error.exit_code()
+        }
+    }
+}
+
+fn run() -> Result<ExitCode, TimesheetError> {
+    let command = Cli::parse().into_command();
+    let (config, durations) = command.common().map(load_config).transpose()?.unwrap_or_default();
+
+    match command {
+        Command::Report(args) => {
+            let today = Local::now().date_naive();
+            let (scan, holidays) = scan_with_holidays(&args.common, &config, today)?;
+            let issues = coverage::find_issues(&scan.summaries, &holidays, &config.schedule, today);
+            let report = report::build(&scan, &issues, &args, &config.schedule, durations, today);
+            print!("{}", report::render(&report, args.format, report::BarStyle::from_args(&args.sections.bars)));
+        }
+        Command::Status(args) => {
+            let today = Local::now().date_naive();
+            let (scan, _) = scan_with_holidays(&args.common, &config, today)?;
+            let weekly_hours = args.target.weekly_hours;
+            let period_str = match (args.current, args.pay.pay_period()) {
+                (CurrentPeriod::PayPeriod, Some(pay_period)) => {
+                    current_pay_period_status(&scan.summaries, durations, &config.schedule, weekly_hours, pay_period, today)
+                }
+                _ => current_week_status(&scan.summaries, durations, weekly_hours, &args.week, today),
+            };
+            print_status_bar_summary(&scan.summaries, durations, &period_str, scan.errors.len());
+        }
+        Command::Log(args) => {
+            let scan = scan_directory(&args.common)?;
+            print!("{}", activity::render_log(&activity::log_rows(&scan.summaries, &args, durations)));
+            print_warnings(&scan.errors);
+        }
+        Command::Check(args) => {
+            let mut scan = scan_directory(&args.common)?;
+            limits::apply(&mut scan.summaries, &config.limits, Local::now().naive_local());
+            let holidays = holidays::load(&args.common.directory, &config.holidays)?;
+            let issues = coverage::find_issues(&scan.summaries, &holidays, &config.schedule, Local::now().date_naive());
+            return Ok(print_check(&args.common.directory, &scan, &issues, durations));
+        }
+        Command::Tui(args) => tui::run(&args, &config, durations)?,
+        Command::Serve(args) => serve::run(&args, &config, durations)?,
+        Command::Chart(args) => {
+            let today = Local::now().date_naive();
+            let (scan, _) = scan_with_holidays(&args.common, &config, today)?;
+            chart::run(&args, &config, durations, &scan.summaries)?;
+            // On stderr, so the SVG on stdout stays valid
+            let warnings: Vec<_> = scan.errors.iter().map(ToString::to_string).collect();
+            eprint!("{}", report::render_warnings(&warnings));
+        }
+        Command::Compliance(args) => {
+            let scan = scan_directory(&args.common)?;
+            let (days, weeks) = compliance::evaluate(&scan.summaries, &config.compliance, &args);
+            print!("{}", compliance::render(&days, &weeks, &config.compliance, args.week.iso_weeks, durations));
+            print_warnings(&scan.errors);
+        }
+        Command::Fix(args) => fix::run(&args, &config.fmt)?,
+        Command::Fmt(args) => return fmt::run(&args, &config.fmt),
+        Command::Start(args) => clock::record(&args, clock::Clock::Start)?,
+        Command::Stop(args) => clock::record(&args, clock::Clock::Stop)?,
+        Command::Export(args) => match args.format {
+            ExportFormat::Csv(common) => {
+                print!("{}", export::to_csv(&scan_directory(&common)?.summaries, durations));
             }
-        });
+            ExportFormat::Json(common) => {
+                println!("{}", export::to_json(&scan_directory(&common)?.summaries, durations));
+            }
+            ExportFormat::Timewarrior(common) => {
+                let intervals = timewarrior::from_summaries(&scan_directory(&common)?.summaries, &Local);
+                println!("{}", timewarrior::to_json(&intervals));
+            }
+            ExportFormat::Ics(common) => {
+                print!("{}", ics::to_ics(&scan_directory(&common)?.summaries, durations, chrono::Utc::now()));
+            }
+        },
+        Command::Import(args) => {
+            let (input, sessions) = match args.source {
+                ImportSource::Timewarrior(input) => {
+                    let intervals = timewarrior::parse_export(&import::read_input(input.file.as_deref())?);
+                    let intervals = intervals.map_err(|error| error.with_path(import_path(&input)))?;
+                    let sessions = timewarrior::to_sessions(&intervals, &Local)?;
+                    (input, sessions)
+                }
+                ImportSource::Toggl(args) | ImportSource::Clockify(args) => {
+                    let text = import::read_input(args.input.file.as_deref())?;
+                    let sessions = csv_import::to_sessions(&text, args.date_format.as_deref());
+                    let sessions = sessions.map_err(|error| error.with_path(import_path(&args.input)))?;
+                    (args.input, sessions)
+                }
+            };
+            import::import_sessions(&input.common.directory, &sessions, input.dry_run)?;
+        }
+        Command::Invoice(args) => {
+            // Never bill from a partial scan, whether or not --strict was given
+            let mut scan = scan_directory(&args.common)?;
+            if !scan.errors.is_empty() {
+                return Err(scan.errors.swap_remove(0));
+            }
+            invoice::run(&args, &config, durations, &scan.summaries)?;
+        }
+        Command::Completions(args) => {
+            clap_complete::generate(args.shell, &mut Cli::command(), "markdown_timesheet", &mut io::stdout());
+        }
+        Command::Man => clap_mangen::Man::new(Cli::command())
+            .render(&mut io::stdout())
+            .map_err(|error| TimesheetError::io(Path::new("<stdout>"), error))?,
+    }
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
 
 #[cfg(test)]
@@ -604,6 +1134,20 @@ mod tests {
         assert_eq!(summary.total_duration.num_minutes() % 60, 30);
     }
 
+    #[test]
+    fn test_notes_and_their_tags() {
+        let parser = TimesheetParser::new(false).unwrap();
+        let content = "# Monday\nStart work 9:00 fixing bugs\n## Details\n#acme follow-up\n- #beta call\n#\nStop work 12:00\n\
+                       Work time 1 hour\n#acme review";
+        let date = NaiveDate::from_ymd_opt(2025, 8, 25).unwrap();
+
+        let summary = parser.parse_file(content, date).unwrap();
+        assert_eq!(summary.entries[0].description, "fixing bugs; follow-up; call");
+        assert_eq!(summary.entries[0].tags, ["acme", "beta"]);
+        assert_eq!(summary.work_time_entries[0].description, "review");
+        assert_eq!(summary.work_time_entries[0].project(), Some("acme"));
+    }
+
     #[test]
     fn test_parse_multiple_entries() {
         let parser = TimesheetParser::new(false).unwrap();
@@ -653,13 +1197,13 @@ Stop work 17:00
     #[test]
     fn test_format_duration() {
         let duration = Duration::hours(8) + Duration::minutes(30);
-        assert_eq!(format_duration(duration), "8h 30m");
+        assert_eq!(DurationFormat::default().format(duration), "8h 30m");
 
         let duration = Duration::hours(0) + Duration::minutes(45);
-        assert_eq!(format_duration(duration), "0h 45m");
+        assert_eq!(DurationFormat::default().format(duration), "0h 45m");
 
         let duration = Duration::hours(10);
-        assert_eq!(format_duration(duration), "10h 00m");
+        assert_eq!(DurationFormat::default().format(duration), "10h 00m");
     }
 
     #[test]
@@ -669,24 +1213,21 @@ Stop work 17:00
             DaySummary {
                 date: NaiveDate::from_ymd_opt(2025, 8, 25).unwrap(), // Monday
                 total_duration: Duration::hours(8),
-                has_tentative: false,
-                has_incomplete: false,
+                ..DaySummary::default()
             },
             DaySummary {
                 date: NaiveDate::from_ymd_opt(2025, 8, 26).unwrap(), // Tuesday
                 total_duration: Duration::hours(7),
-                has_tentative: false,
-                has_incomplete: false,
+                ..DaySummary::default()
             },
             DaySummary {
                 date: NaiveDate::from_ymd_opt(2025, 9, 1).unwrap(), // Next Monday
                 total_duration: Duration::hours(6),
-                has_tentative: false,
-                has_incomplete: false,
+                ..DaySummary::default()
             },
         ];
 
-        let weeks = TimesheetParser::group_by_week(&summaries);
+        let weeks = TimesheetParser::group_by_week(&summaries, Weekday::Mon);
         assert_eq!(weeks.len(), 2);
         
         assert_eq!(weeks[0].days.len(), 2);
@@ -694,6 +1235,145 @@ Stop work 17:00
         
         assert_eq!(weeks[1].days.len(), 1);
         assert_eq!(weeks[1].total_duration.num_hours(), 6);
+
+        // With Sunday-start weeks the Monday 2025-09-01 joins the week of Sunday 2025-08-31
+        let weeks = TimesheetParser::group_by_week(&summaries, Weekday::Sun);
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(weeks[0].week_start, NaiveDate::from_ymd_opt(2025, 8, 24).unwrap());
+        assert_eq!(weeks[1].week_start, NaiveDate::from_ymd_opt(2025, 8, 31).unwrap());
+        assert_eq!(weeks[1].week_end(), NaiveDate::from_ymd_opt(2025, 9, 6).unwrap());
+
+        // Saturday-start weeks put all three days into the week of Saturday 2025-08-23 and 2025-08-30
+        let weeks = TimesheetParser::group_by_week(&summaries, Weekday::Sat);
+        assert_eq!(weeks[0].week_start, NaiveDate::from_ymd_opt(2025, 8, 23).unwrap());
+        assert_eq!(weeks[1].week_start, NaiveDate::from_ymd_opt(2025, 8, 30).unwrap());
+    }
+
+    #[test]
+    fn test_week_labels() {
+        let week = |start: &str| WeekSummary {
+            week_start: start.parse().unwrap(),
+            total_duration: Duration::zero(),
+            days: Vec::new(),
+        };
+
+        assert_eq!(week("2025-08-25").label(false), "Week of 2025-08-25 - 2025-08-31");
+        assert_eq!(week("2025-08-25").label(true), "2025-W35");
+        // A Sunday-start week shares six days with the following ISO week
+        assert_eq!(week("2025-08-24").label(true), "2025-W35");
+        // A Saturday-start week shares five days with the following ISO week
+        assert_eq!(week("2025-08-23").label(true), "2025-W35");
+        // ISO years differ from calendar years around New Year
+        assert_eq!(week("2024-12-30").label(true), "2025-W01");
+        assert_eq!(week("2027-01-03").label(true), "2027-W01");
+    }
+
+    #[test]
+    fn test_group_by_quarter_and_year() {
+        let parser = TimesheetParser::new(false).unwrap();
+        let day = |date: &str, content: &str| parser.parse_file(content, date.parse().unwrap()).unwrap();
+        let summaries = vec![
+            day("2024-12-31", "Start work 9:00\nStop work 17:00"),
+            day("2025-01-02", "Start work 9:00\nStop work 16:00"),
+            day("2025-03-31", "PTO"),
+            day("2025-04-01", "Start work 9:00\nStop work 13:00\nWork time 2 hours review"),
+        ];
+
+        let quarters = TimesheetParser::group_by_quarter(&summaries);
+        let labels: Vec<_> = quarters.iter().map(|quarter| quarter.label.as_str()).collect();
+        assert_eq!(labels, ["Q4 2024", "Q1 2025", "Q2 2025"]);
+
+        let q1 = &quarters[1];
+        assert_eq!(q1.start, NaiveDate::from_ymd_opt(2025, 1, 1).unwrap());
+        assert_eq!(q1.end, NaiveDate::from_ymd_opt(2025, 3, 31).unwrap());
+        assert_eq!(q1.total_duration, Duration::hours(15));
+        assert_eq!(q1.leave_duration, Duration::hours(8));
+        assert_eq!(q1.leave_days, 1);
+        assert_eq!(q1.worked_days, 1);
+        assert_eq!(q1.average_worked_day(), Some(Duration::hours(7)));
+
+        let years = TimesheetParser::group_by_year(&summaries);
+        assert_eq!(years.len(), 2);
+        assert_eq!(years[1].label, "2025");
+        assert_eq!(years[1].total_duration, Duration::hours(21));
+        assert_eq!(years[1].worked_days, 2);
+        assert_eq!(years[1].average_worked_day(), Some(Duration::hours(6) + Duration::minutes(30)));
+    }
+
+    #[test]
+    fn test_period_expected_duration() {
+        let period = PeriodSummary {
+            label: "Q3 2025".to_string(),
+            start: NaiveDate::from_ymd_opt(2025, 7, 1).unwrap(),
+            end: NaiveDate::from_ymd_opt(2025, 9, 30).unwrap(),
+            total_duration: Duration::zero(),
+            leave_duration: Duration::zero(),
+            leave_days: 0,
+            worked_days: 0,
+        };
+        let after = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let schedule = ScheduleConfig::default();
+
+        // 66 weekdays in Q3 2025 at 8 hours each
+        let tracking_start = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        assert_eq!(period.expected_duration(&schedule, 40.0, tracking_start, after), Duration::hours(528));
+
+        // Tracking began on Monday 2025-09-22, today is Wednesday 2025-09-24
+        let tracking_start = NaiveDate::from_ymd_opt(2025, 9, 22).unwrap();
+        let today = NaiveDate::from_ymd_opt(2025, 9, 24).unwrap();
+        assert_eq!(period.expected_duration(&schedule, 37.5, tracking_start, today), Duration::minutes(1350));
+        assert_eq!(period.average_worked_day(), None);
+
+        // A four-day week expects 10 hours on each of Q3's 53 Mondays to Thursdays
+        let schedule = ScheduleConfig { workdays: vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu], ..ScheduleConfig::default() };
+        let tracking_start = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        assert_eq!(period.expected_duration(&schedule, 40.0, tracking_start, after), Duration::hours(530));
+    }
+
+    #[test]
+    fn test_pay_period_bounds() {
+        let date = |value: &str| value.parse::<NaiveDate>().unwrap();
+        let biweekly = PayPeriod::Biweekly { anchor: date("2025-08-18") };
+
+        assert_eq!(biweekly.bounds(date("2025-08-18")), Some((date("2025-08-18"), date("2025-08-31"))));
+        assert_eq!(biweekly.bounds(date("2025-08-31")), Some((date("2025-08-18"), date("2025-08-31"))));
+        assert_eq!(biweekly.bounds(date("2025-09-01")), Some((date("2025-09-01"), date("2025-09-14"))));
+        // Dates before the anchor fall into earlier periods
+        assert_eq!(biweekly.bounds(date("2025-08-17")), Some((date("2025-08-04"), date("2025-08-17"))));
+
+        let semi_monthly = PayPeriod::SemiMonthly;
+        assert_eq!(semi_monthly.bounds(date("2025-02-15")), Some((date("2025-02-01"), date("2025-02-15"))));
+        assert_eq!(semi_monthly.bounds(date("2025-02-16")), Some((date("2025-02-16"), date("2025-02-28"))));
+        assert_eq!(semi_monthly.bounds(date("2024-02-20")), Some((date("2024-02-16"), date("2024-02-29"))));
+        assert_eq!(semi_monthly.bounds(date("2025-12-31")), Some((date("2025-12-16"), date("2025-12-31"))));
+    }
+
+    #[test]
+    fn test_group_by_pay_period() {
+        let parser = TimesheetParser::new(false).unwrap();
+        let day = |date: &str| parser.parse_file("Start work 9:00\nStop work 17:00", date.parse().unwrap()).unwrap();
+        let summaries = vec![day("2025-08-29"), day("2025-09-01"), day("2025-09-15"), day("2025-09-16")];
+
+        let periods = TimesheetParser::group_by_pay_period(&summaries, PayPeriod::SemiMonthly);
+        let labels: Vec<_> = periods.iter().map(|period| period.label.as_str()).collect();
+        assert_eq!(labels, ["2025-08-16 - 2025-08-31", "2025-09-01 - 2025-09-15", "2025-09-16 - 2025-09-30"]);
+        assert_eq!(periods[1].total_duration, Duration::hours(16));
+
+        let anchor = NaiveDate::from_ymd_opt(2025, 8, 18).unwrap();
+        let periods = TimesheetParser::group_by_pay_period(&summaries, PayPeriod::Biweekly { anchor });
+        assert_eq!(periods.len(), 3);
+        assert_eq!(periods[1].label, "2025-09-01 - 2025-09-14");
+        assert_eq!(periods[1].total_duration, Duration::hours(8));
+        assert_eq!(periods[2].total_duration, Duration::hours(16));
+        // A full biweekly period has ten weekdays
+        assert_eq!(periods[1].expected_duration(&ScheduleConfig::default(), 40.0, periods[1].start, periods[1].end), Duration::hours(80));
+    }
+
+    #[test]
+    fn test_format_balance() {
+        assert_eq!(format_balance(DurationFormat::default(), Duration::hours(30), Duration::hours(40)), " [10h 00m short]");
+        assert_eq!(format_balance(DurationFormat::default(), Duration::minutes(2430), Duration::hours(40)), " [0h 30m over]");
+        assert_eq!(format_balance(DurationFormat::default(), Duration::hours(40), Duration::hours(40)), "");
     }
 
     #[test]
@@ -982,8 +1662,8 @@ Stat holiday
     #[test] 
     fn test_format_duration_with_tentative() {
         let duration = Duration::hours(5) + Duration::minutes(30);
-        assert_eq!(format_duration_with_flags(duration, false, false), "5h 30m");
-        assert_eq!(format_duration_with_flags(duration, true, false), "5h 30m *");
+        assert_eq!(format_duration_with_flags(DurationFormat::default(), duration, false, false, false), "5h 30m");
+        assert_eq!(format_duration_with_flags(DurationFormat::default(), duration, true, false, false), "5h 30m *");
     }
 
     #[test]
@@ -1088,20 +1768,113 @@ Stop work 17:00
         assert_eq!(summary.total_duration, Duration::hours(3)); // Only the valid 9-12 entry counts
     }
 
+    #[test]
+    fn test_parse_directory_is_sorted_and_deterministic() {
+        let dir = std::env::temp_dir().join(format!("markdown_timesheet_parse_directory_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        for date in start.iter_days().take(100) {
+            fs::write(dir.join(format!("{date}.md")), "Start work 9:00\nStop work 17:00").unwrap();
+        }
+        fs::write(dir.join("notes.md"), "Start work 9:00\nStop work 17:00").unwrap();
+
+        let parser = TimesheetParser::new(false).unwrap();
+        let first = parser.parse_directory(&dir).unwrap().summaries;
+        let second = parser.parse_directory(&dir).unwrap().summaries;
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(first.len(), 100);
+        assert!(first.windows(2).all(|pair| pair[0].date < pair[1].date));
+        assert_eq!(
+            first.iter().map(|day| day.date).collect::<Vec<_>>(),
+            second.iter().map(|day| day.date).collect::<Vec<_>>()
+        );
+        assert!(first.iter().all(|day| day.total_duration == Duration::hours(8)));
+    }
+
+    #[test]
+    fn test_parse_directory_collects_bad_files() {
+        let dir = std::env::temp_dir().join(format!("markdown_timesheet_bad_files_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("2025-08-25.md"), "Start work 9:00\nStop work 17:00").unwrap();
+        fs::write(dir.join("2025-08-26.md"), b"Start work 9:00\n\xff\xfe\nStop work 17:00").unwrap();
+        fs::write(dir.join("2025-08-27.md"), "Work time 99999999999 hours").unwrap();
+        fs::write(dir.join("2025-08-28.md"), "Start work 10:00\nStop work 12:00").unwrap();
+
+        let parser = TimesheetParser::new(false).unwrap();
+        let scan = parser.parse_directory(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let dates: Vec<_> = scan.summaries.iter().map(|day| day.date.to_string()).collect();
+        assert_eq!(dates, ["2025-08-25", "2025-08-28"]);
+        assert_eq!(scan.errors.len(), 2);
+        assert!(matches!(&scan.errors[0], TimesheetError::Io { path, .. } if path.ends_with("2025-08-26.md")));
+        assert!(matches!(&scan.errors[1], TimesheetError::Parse { path: Some(path), line: 1, .. } if path.ends_with("2025-08-27.md")));
+    }
+
+    #[test]
+    fn test_parse_directory_missing_directory_is_fatal() {
+        let parser = TimesheetParser::new(false).unwrap();
+        let dir = std::env::temp_dir().join("markdown_timesheet_does_not_exist");
+        let error = parser.parse_directory(&dir).unwrap_err();
+        assert!(matches!(error, TimesheetError::Io { .. }));
+        assert_eq!(error.exit_code(), ExitCode::from(74));
+    }
+
+    #[test]
+    fn test_parse_error_reports_line() {
+        let parser = TimesheetParser::new(false).unwrap();
+        let content = "Start work 9:00\nStop work 12:00\nWork time 99999999999 hours";
+        let date = NaiveDate::from_ymd_opt(2025, 8, 25).unwrap();
+
+        let error = parser.parse_file(content, date).unwrap_err();
+        assert!(matches!(error, TimesheetError::Parse { line: 3, .. }));
+        assert_eq!(error.exit_code(), ExitCode::from(65));
+
+        let error = error.with_path(Path::new("2025-08-25.md"));
+        assert!(error.to_string().starts_with("2025-08-25.md:3: invalid number \"99999999999\""));
+    }
+
+    #[test]
+    fn test_diagnostics_have_line_numbers() {
+        let parser = TimesheetParser::new(false).unwrap();
+        let content = "Start work 9:00\nStart work 10:00\nStop work 12:00\nStop work 13:00\nStart work 25:00";
+        let date = NaiveDate::from_ymd_opt(2025, 8, 25).unwrap();
+
+        let summary = parser.parse_file(content, date).unwrap();
+        let lines: Vec<_> = summary.diagnostics.iter().map(|diagnostic| diagnostic.line).collect();
+        assert_eq!(lines, [1, 4, 5]);
+        assert!(matches!(summary.diagnostics[0].kind, DiagnosticKind::OpenSession { .. }));
+        assert!(matches!(summary.diagnostics[1].kind, DiagnosticKind::OrphanedStop { .. }));
+        assert!(matches!(summary.diagnostics[2].kind, DiagnosticKind::InvalidTime { .. }));
+        assert_eq!(summary.diagnostics[0].kind.describe(DurationFormat::default()), "session started at 09:00 has no matching stop");
+    }
+
+    #[test]
+    fn test_tentative_session_is_not_a_diagnostic() {
+        let parser = TimesheetParser::new(false).unwrap();
+        let today = Local::now().date_naive();
+
+        let summary = parser.parse_file("Start work 0:00", today).unwrap();
+        assert!(summary.diagnostics.is_empty());
+    }
+
     #[test]
     fn test_format_duration_with_flags() {
         let duration = Duration::hours(5) + Duration::minutes(30);
         
         // No flags
-        assert_eq!(format_duration_with_flags(duration, false, false), "5h 30m");
+        assert_eq!(format_duration_with_flags(DurationFormat::default(), duration, false, false, false), "5h 30m");
         
         // Tentative only
-        assert_eq!(format_duration_with_flags(duration, true, false), "5h 30m *");
+        assert_eq!(format_duration_with_flags(DurationFormat::default(), duration, true, false, false), "5h 30m *");
         
         // Incomplete only
-        assert_eq!(format_duration_with_flags(duration, false, true), "5h 30m E!");
+        assert_eq!(format_duration_with_flags(DurationFormat::default(), duration, false, true, false), "5h 30m E!");
         
         // Both flags
-        assert_eq!(format_duration_with_flags(duration, true, true), "5h 30m * E!");
+        assert_eq!(format_duration_with_flags(DurationFormat::default(), duration, true, true, false), "5h 30m * E!");
+        assert_eq!(format_duration_with_flags(DurationFormat::default(), duration, false, false, true), "5h 30m L!");
+        assert_eq!(format_duration_with_flags(DurationFormat::default(), duration, true, true, true), "5h 30m * E! L!");
     }
 }
\ No newline at end of file
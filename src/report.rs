@@ -0,0 +1,635 @@
+use crate::cli::{BarArgs, ReportArgs, ReportFormat};
+use crate::config::ScheduleConfig;
+use crate::coverage::{CoverageIssue, Finding};
+use crate::invoice::escape_html;
+use crate::duration::DurationFormat;
+use crate::{format_balance, format_duration_with_flags, get_month_name, hours_to_duration};
+use crate::{DirectoryScan, PeriodSummary, TimesheetParser};
+use chrono::{Datelike, Duration, NaiveDate};
+use ratatui::crossterm::terminal;
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write as _;
+use std::io::{self, IsTerminal};
+
+/// One day of the daily section
+#[derive(Debug, PartialEq)]
+pub struct DailyRow {
+    pub date: NaiveDate,
+    /// The day's total with its status flags, or `None` for a missing file
+    pub total: Option<String>,
+    /// Holiday name and missing-day findings
+    pub notes: Vec<String>,
+    /// Whether a missing-day finding is among the notes
+    pub flagged: bool,
+    pub worked: Duration,
+    /// The daily share of the weekly target on scheduled workdays that are not holidays
+    pub expected: Duration,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MonthlyRow {
+    pub year: i32,
+    pub month: u32,
+    pub total: Duration,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct WeeklyRow {
+    pub label: String,
+    pub total: Duration,
+    pub expected: Duration,
+}
+
+impl WeeklyRow {
+    fn shortage(&self) -> Option<Duration> {
+        (self.total < self.expected).then(|| self.expected - self.total)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PeriodRow {
+    pub label: String,
+    pub total: Duration,
+    pub expected: Duration,
+    pub leave_days: usize,
+    /// Hours per day with logged work, leave left out
+    pub average: Option<Duration>,
+}
+
+impl PeriodRow {
+    fn new(period: &PeriodSummary, schedule: &ScheduleConfig, weekly_hours: f64, tracking_start: NaiveDate, today: NaiveDate) -> Self {
+        Self {
+            label: period.label.clone(),
+            total: period.total_duration,
+            expected: period.expected_duration(schedule, weekly_hours, tracking_start, today),
+            leave_days: period.leave_days,
+            average: period.average_worked_day(),
+        }
+    }
+
+    fn leave(&self) -> String {
+        let noun = if self.leave_days == 1 { "leave day" } else { "leave days" };
+        format!("{} {noun}", self.leave_days)
+    }
+
+    fn average(&self, durations: DurationFormat) -> String {
+        self.average
+            .map_or_else(|| "no worked days".to_string(), |average| format!("{} per worked day", durations.format(average)))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PeriodSection {
+    pub title: &'static str,
+    pub rows: Vec<PeriodRow>,
+}
+
+/// Everything `report` shows, independent of the output format
+#[derive(Debug, PartialEq)]
+pub struct Report {
+    pub today: NaiveDate,
+    /// How hours are written in every section
+    pub durations: DurationFormat,
+    pub daily: Vec<DailyRow>,
+    pub monthly: Vec<MonthlyRow>,
+    pub weekly: Vec<WeeklyRow>,
+    pub periods: Vec<PeriodSection>,
+    /// Files that were skipped because they could not be read
+    pub warnings: Vec<String>,
+}
+
+const DAILY_TITLE: &str = "Daily Summary (Last 2 Weeks)";
+const MONTHLY_TITLE: &str = "Monthly Summary";
+const WEEKLY_TITLE: &str = "Weekly Summary";
+const WARNINGS_TITLE: &str = "Warnings";
+
+/// The report for the days in `scan`: the last two weeks day by day, then
+/// months, weeks and any requested longer periods that have time in them
+pub fn build(scan: &DirectoryScan, issues: &[CoverageIssue], args: &ReportArgs, schedule: &ScheduleConfig, durations: DurationFormat, today: NaiveDate) -> Report {
+    let weekly_hours = args.target.weekly_hours;
+    let week_options = &args.week;
+    let weeks = TimesheetParser::group_by_week(&scan.summaries, week_options.week_start);
+    let two_weeks_ago = today - Duration::days(14);
+    let daily_target = schedule.daily_target(hours_to_duration(weekly_hours));
+    let expected_on = |date: NaiveDate| if schedule.workdays.contains(&date.weekday()) { daily_target } else { Duration::zero() };
+
+    let mut daily = BTreeMap::new();
+    for day in weeks.iter().flat_map(|week| &week.days).filter(|day| day.date >= two_weeks_ago) {
+        let findings: Vec<_> = issues.iter().filter(|issue| issue.date == day.date).map(|issue| issue.finding.short(durations)).collect();
+        let flagged = !findings.is_empty();
+        let mut notes: Vec<_> = day.holiday.iter().map(|name| format!("holiday: {name}")).collect();
+        notes.extend(findings);
+        if day.total_duration > Duration::zero() || day.has_incomplete || !notes.is_empty() {
+            let total = format_duration_with_flags(durations, day.total_duration, day.has_tentative, day.has_incomplete, day.is_implausible());
+            let expected = if day.holiday.is_some() { Duration::zero() } else { expected_on(day.date) };
+            let row = DailyRow { date: day.date, total: Some(total), notes, flagged, worked: day.total_duration, expected };
+            daily.insert(day.date, row);
+        }
+    }
+    for issue in issues.iter().filter(|issue| issue.finding == Finding::MissingFile && issue.date >= two_weeks_ago) {
+        let row = DailyRow {
+            date: issue.date,
+            total: None,
+            notes: vec![issue.finding.short(durations)],
+            flagged: true,
+            worked: Duration::zero(),
+            expected: expected_on(issue.date),
+        };
+        daily.insert(issue.date, row);
+    }
+
+    let monthly = TimesheetParser::group_by_month(&scan.summaries)
+        .into_iter()
+        .filter(|month| month.total_duration > Duration::zero())
+        .map(|month| MonthlyRow { year: month.year, month: month.month, total: month.total_duration })
+        .collect();
+
+    let expected = hours_to_duration(weekly_hours);
+    let weekly = weeks
+        .iter()
+        .filter(|week| week.total_duration > Duration::zero())
+        .map(|week| WeeklyRow { label: week.label(week_options.iso_weeks), total: week.total_duration, expected })
+        .collect();
+
+    let mut periods = Vec::new();
+    if let Some(tracking_start) = scan.summaries.first().map(|day| day.date) {
+        let mut section = |title, summaries: Vec<PeriodSummary>| {
+            let rows = summaries
+                .iter()
+                .filter(|period| period.total_duration > Duration::zero())
+                .map(|period| PeriodRow::new(period, schedule, weekly_hours, tracking_start, today))
+                .collect();
+            periods.push(PeriodSection { title, rows });
+        };
+        if let Some(pay_period) = args.pay.pay_period() {
+            section("Pay Period Summary", TimesheetParser::group_by_pay_period(&scan.summaries, pay_period));
+        }
+        if args.sections.quarterly {
+            section("Quarterly Summary", TimesheetParser::group_by_quarter(&scan.summaries));
+        }
+        if args.sections.yearly {
+            section("Yearly Summary", TimesheetParser::group_by_year(&scan.summaries));
+        }
+    }
+
+    Report {
+        today,
+        durations,
+        daily: daily.into_values().collect(),
+        monthly,
+        weekly,
+        periods,
+        warnings: scan.errors.iter().map(ToString::to_string).collect(),
+    }
+}
+
+/// Unicode blocks filling one to seven eighths of a cell
+const PARTIAL_BLOCKS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// How `--bars` draws the bars next to daily and weekly totals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BarStyle {
+    /// Width of the terminal the report is printed to
+    pub columns: usize,
+    pub unicode: bool,
+}
+
+impl BarStyle {
+    /// Bars sized for the terminal, or `None` without `--bars`
+    pub fn from_args(bars: &BarArgs) -> Option<Self> {
+        bars.enabled.then(|| Self { columns: terminal_columns(), unicode: !bars.no_unicode })
+    }
+
+    /// `(full, empty, target marker)` characters
+    fn characters(self) -> (char, char, char) {
+        if self.unicode {
+            ('█', '·', '│')
+        } else {
+            ('#', '.', '|')
+        }
+    }
+
+    /// A bar of up to `cells` characters, filled up to `worked`, with a marker
+    /// at `expected` and dots up to the marker when short of it; `max` fills
+    /// all but the last cell, which is left for the marker
+    fn bar(self, worked: Duration, expected: Duration, max: Duration, cells: usize) -> String {
+        let (full, empty, marker) = self.characters();
+        let max = max.num_minutes().max(1);
+        let scaled = |duration: Duration| {
+            let eighths = i64::try_from((cells - 1) * 8).unwrap_or(i64::MAX);
+            usize::try_from((duration.num_minutes().clamp(0, max) * eighths + max / 2) / max).unwrap_or_default()
+        };
+        // In eighths of a cell; ASCII has no partial blocks, so it rounds to whole cells
+        let filled = if self.unicode { scaled(worked) } else { (scaled(worked) + 4) / 8 * 8 };
+        let target = (expected > Duration::zero()).then(|| scaled(expected) / 8);
+
+        let mut bar = String::new();
+        for cell in 0..cells {
+            let character = if Some(cell) == target {
+                marker
+            } else if cell < filled / 8 {
+                full
+            } else if cell == filled / 8 && filled % 8 > 0 {
+                PARTIAL_BLOCKS[filled % 8]
+            } else if target.is_some_and(|target| cell < target) {
+                empty
+            } else {
+                break;
+            };
+            bar.push(character);
+        }
+        bar
+    }
+}
+
+/// Columns of the terminal on stdout, then `$COLUMNS`, then 80
+fn terminal_columns() -> usize {
+    let terminal = io::stdout().is_terminal().then(terminal::size).and_then(Result::ok);
+    terminal
+        .map(|(columns, _)| usize::from(columns))
+        .or_else(|| env::var("COLUMNS").ok()?.parse().ok())
+        .filter(|columns| *columns > 0)
+        .unwrap_or(80)
+}
+
+/// A line of the daily or weekly section: the total, then notes or the shortage
+struct TextLine {
+    total: String,
+    suffix: String,
+    worked: Duration,
+    expected: Duration,
+}
+
+/// Write `lines`, with bars between the totals and the rest when `bars` is set;
+/// the bars share one column and one scale, the longest bar or target
+fn write_lines(out: &mut String, lines: &[TextLine], bars: Option<BarStyle>) {
+    let Some(style) = bars else {
+        for line in lines {
+            let _ = writeln!(out, "{}{}", line.total, line.suffix);
+        }
+        return;
+    };
+    let width = lines.iter().map(|line| line.total.chars().count()).max().unwrap_or_default();
+    let suffix = lines.iter().map(|line| line.suffix.chars().count()).max().unwrap_or_default();
+    let max = lines.iter().flat_map(|line| [line.worked, line.expected]).max().unwrap_or_default();
+    let cells = style.columns.saturating_sub(width + suffix + 3).clamp(10, 50);
+    for line in lines {
+        let bar = style.bar(line.worked, line.expected, max, cells);
+        let text = format!("{:width$}  {bar:cells$}{}", line.total, line.suffix);
+        let _ = writeln!(out, "{}", text.trim_end());
+    }
+}
+
+pub fn render(report: &Report, format: ReportFormat, bars: Option<BarStyle>) -> String {
+    match format {
+        ReportFormat::Text => render_text(report, bars),
+        ReportFormat::Markdown => render_markdown(report),
+        ReportFormat::Html => render_html(report),
+    }
+}
+
+/// The list of skipped files printed after text reports and listings
+pub fn render_warnings(warnings: &[String]) -> String {
+    if warnings.is_empty() {
+        return String::new();
+    }
+    let mut out = String::new();
+    let _ = writeln!(out, "\n{WARNINGS_TITLE}:\n{}", "=".repeat(WARNINGS_TITLE.len() + 1));
+    for warning in warnings {
+        let _ = writeln!(out, "{warning}");
+    }
+    let count = warnings.len();
+    let noun = if count == 1 { "file" } else { "files" };
+    let _ = writeln!(out, "{count} {noun} skipped; run with --strict to fail instead");
+    out
+}
+
+fn text_heading(out: &mut String, title: &str) {
+    let _ = writeln!(out, "{title}:\n{}", "=".repeat(title.len() + 1));
+}
+
+fn render_text(report: &Report, bars: Option<BarStyle>) -> String {
+    let mut out = format!("{DAILY_TITLE}:\n==============================\n");
+    let daily: Vec<_> = report
+        .daily
+        .iter()
+        .map(|row| {
+            let day = format!("{} {:3} -", row.date, row.date.format("%a"));
+            let (total, suffix) = match (&row.total, row.notes.is_empty()) {
+                (Some(total), true) => (format!("{day} {total}"), String::new()),
+                (Some(total), false) => (format!("{day} {total}"), format!(" ({})", row.notes.join(", "))),
+                (None, _) => (format!("{day} {}", row.notes.join(", ")), String::new()),
+            };
+            TextLine { total, suffix, worked: row.worked, expected: row.expected }
+        })
+        .collect();
+    write_lines(&mut out, &daily, bars);
+
+    out.push('\n');
+    text_heading(&mut out, MONTHLY_TITLE);
+    for row in &report.monthly {
+        let _ = writeln!(out, "{} {}: {}", get_month_name(row.month), row.year, report.durations.format(row.total));
+    }
+
+    out.push('\n');
+    text_heading(&mut out, WEEKLY_TITLE);
+    let weekly: Vec<_> = report
+        .weekly
+        .iter()
+        .map(|row| TextLine {
+            total: format!("{}: {}", row.label, report.durations.format(row.total)),
+            suffix: row.shortage().map(|shortage| format!(" [{} short]", report.durations.format(shortage))).unwrap_or_default(),
+            worked: row.total,
+            expected: row.expected,
+        })
+        .collect();
+    write_lines(&mut out, &weekly, bars);
+
+    for section in &report.periods {
+        out.push('\n');
+        text_heading(&mut out, section.title);
+        for row in &section.rows {
+            let _ = writeln!(
+                out,
+                "{}: {} of {} expected{}, {}, {}",
+                row.label,
+                report.durations.format(row.total),
+                report.durations.format(row.expected),
+                format_balance(report.durations, row.total, row.expected),
+                row.leave(),
+                row.average(report.durations)
+            );
+        }
+    }
+
+    out.push_str(&render_warnings(&report.warnings));
+    out
+}
+
+/// A section as a table: its title, column headers and rows, each marked when it needs attention
+struct Table {
+    title: &'static str,
+    headers: &'static [&'static str],
+    rows: Vec<(Vec<String>, bool)>,
+}
+
+/// The balance column of weekly and period tables, e.g. "10h 00m short"
+fn balance(durations: DurationFormat, total: Duration, expected: Duration) -> String {
+    format_balance(durations, total, expected).trim().trim_start_matches('[').trim_end_matches(']').to_string()
+}
+
+fn tables(report: &Report) -> Vec<Table> {
+    let mut tables = vec![
+        Table {
+            title: DAILY_TITLE,
+            headers: &["Date", "Day", "Hours", "Notes"],
+            rows: report
+                .daily
+                .iter()
+                .map(|row| {
+                    let cells = vec![
+                        row.date.to_string(),
+                        row.date.format("%a").to_string(),
+                        row.total.clone().unwrap_or_default(),
+                        row.notes.join(", "),
+                    ];
+                    (cells, row.flagged)
+                })
+                .collect(),
+        },
+        Table {
+            title: MONTHLY_TITLE,
+            headers: &["Month", "Hours"],
+            rows: report
+                .monthly
+                .iter()
+                .map(|row| (vec![format!("{} {}", get_month_name(row.month), row.year), report.durations.format(row.total)], false))
+                .collect(),
+        },
+        Table {
+            title: WEEKLY_TITLE,
+            headers: &["Week", "Hours", "Target", "Short"],
+            rows: report
+                .weekly
+                .iter()
+                .map(|row| {
+                    let short = row.shortage().map(|shortage| report.durations.format(shortage)).unwrap_or_default();
+                    (vec![row.label.clone(), report.durations.format(row.total), report.durations.format(row.expected), short], row.shortage().is_some())
+                })
+                .collect(),
+        },
+    ];
+
+    for section in &report.periods {
+        tables.push(Table {
+            title: section.title,
+            headers: &["Period", "Hours", "Expected", "Balance", "Leave", "Average"],
+            rows: section
+                .rows
+                .iter()
+                .map(|row| {
+                    let cells = vec![
+                        row.label.clone(),
+                        report.durations.format(row.total),
+                        report.durations.format(row.expected),
+                        balance(report.durations, row.total, row.expected),
+                        row.leave(),
+                        row.average(report.durations),
+                    ];
+                    (cells, row.total < row.expected)
+                })
+                .collect(),
+        });
+    }
+    tables
+}
+
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+/// Markdown tables that paste cleanly into wikis; rows that need attention are bold
+fn render_markdown(report: &Report) -> String {
+    let mut out = format!("# Timesheet Report\n\nGenerated {}\n", report.today);
+    for table in tables(report) {
+        let _ = writeln!(out, "\n## {}\n", table.title);
+        let _ = writeln!(out, "| {} |", table.headers.join(" | "));
+        let _ = writeln!(out, "|{}", " --- |".repeat(table.headers.len()));
+        for (cells, highlight) in &table.rows {
+            let cells: Vec<_> = cells
+                .iter()
+                .map(|cell| match (highlight, cell.is_empty()) {
+                    (true, false) => format!("**{}**", escape_markdown(cell)),
+                    _ => escape_markdown(cell),
+                })
+                .collect();
+            let _ = writeln!(out, "| {} |", cells.join(" | "));
+        }
+    }
+
+    if !report.warnings.is_empty() {
+        let _ = writeln!(out, "\n## {WARNINGS_TITLE}\n");
+        for warning in &report.warnings {
+            let _ = writeln!(out, "- {}", escape_markdown(warning));
+        }
+    }
+    out
+}
+
+const REPORT_CSS: &str = "body { font-family: -apple-system, 'Segoe UI', Helvetica, Arial, sans-serif; color: #222; max-width: 60rem; margin: 2rem auto; padding: 0 1rem; }
+h1 { font-size: 1.8rem; margin-bottom: 0.25rem; }
+h2 { font-size: 1.2rem; margin-top: 2rem; }
+.generated { color: #666; }
+table { width: 100%; border-collapse: collapse; }
+th, td { padding: 0.3rem 0.6rem; border-bottom: 1px solid #ddd; text-align: left; }
+th { background: #f4f4f4; }
+tr.short td { background: #fdecea; color: #8a1c12; }
+.warnings li { color: #8a1c12; }
+";
+
+/// A single HTML file with inline styles; shortages and missing days are highlighted
+fn render_html(report: &Report) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">");
+    let _ = writeln!(out, "<title>Timesheet Report {}</title>", report.today);
+    let _ = writeln!(out, "<style>\n{REPORT_CSS}</style>\n</head>\n<body>");
+    let _ = writeln!(out, "<h1>Timesheet Report</h1>\n<p class=\"generated\">Generated {}</p>", report.today);
+
+    for table in tables(report) {
+        let _ = writeln!(out, "<h2>{}</h2>\n<table>", escape_html(table.title));
+        out.push_str("<tr>");
+        for header in table.headers {
+            let _ = write!(out, "<th>{header}</th>");
+        }
+        out.push_str("</tr>\n");
+        for (cells, highlight) in &table.rows {
+            out.push_str(if *highlight { "<tr class=\"short\">" } else { "<tr>" });
+            for cell in cells {
+                let _ = write!(out, "<td>{}</td>", escape_html(cell));
+            }
+            out.push_str("</tr>\n");
+        }
+        let _ = writeln!(out, "</table>");
+    }
+
+    if !report.warnings.is_empty() {
+        let _ = writeln!(out, "<h2>{WARNINGS_TITLE}</h2>\n<ul class=\"warnings\">");
+        for warning in &report.warnings {
+            let _ = writeln!(out, "<li>{}</li>", escape_html(warning));
+        }
+        let _ = writeln!(out, "</ul>");
+    }
+    let _ = writeln!(out, "</body>\n</html>");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Command, ReportArgs};
+    use clap::Parser;
+
+    fn args(extra: &[&str]) -> ReportArgs {
+        let argv = ["markdown_timesheet", "report"].into_iter().chain(extra.iter().copied());
+        match Cli::parse_from(argv).into_command() {
+            Command::Report(args) => args,
+            command => panic!("unexpected command {command:?}"),
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 9, day).unwrap()
+    }
+
+    fn report(extra: &[&str]) -> Report {
+        report_in(extra, DurationFormat::default())
+    }
+
+    fn report_in(extra: &[&str], durations: DurationFormat) -> Report {
+        let parser = TimesheetParser::new(false).unwrap();
+        let mut holiday = parser.parse_file("", date(1)).unwrap();
+        holiday.holiday = Some("Labor <Day>".to_string());
+        holiday.total_duration = Duration::hours(8);
+        holiday.leave_duration = Duration::hours(8);
+        let scan = DirectoryScan {
+            summaries: vec![
+                holiday,
+                parser.parse_file("Start work 9:00\nStop work 17:00", date(2)).unwrap(),
+                parser.parse_file("Start work 9:00\nStop work 12:00\nStart work 15:00\nStop work 20:00", date(4)).unwrap(),
+            ],
+            errors: Vec::new(),
+        };
+        let issues = [
+            CoverageIssue { date: date(3), finding: Finding::MissingFile },
+            CoverageIssue { date: date(4), finding: Finding::Gap { from: chrono::NaiveTime::from_hms_opt(12, 0, 0).unwrap(), to: chrono::NaiveTime::from_hms_opt(15, 0, 0).unwrap(), line: 3 } },
+        ];
+        build(&scan, &issues, &args(extra), &ScheduleConfig::default(), durations, date(5))
+    }
+
+    #[test]
+    fn test_text_report() {
+        let mut report = report(&["--yearly"]);
+        report.warnings.push("2025-09-05.md: unreadable".to_string());
+        assert_eq!(
+            render(&report, ReportFormat::Text, None),
+            "Daily Summary (Last 2 Weeks):\n==============================\n\
+             2025-09-01 Mon - 8h 00m (holiday: Labor <Day>)\n\
+             2025-09-02 Tue - 8h 00m\n\
+             2025-09-03 Wed - no file\n\
+             2025-09-04 Thu - 8h 00m (3h 00m gap after 12:00)\n\
+             \nMonthly Summary:\n================\nSeptember 2025: 24h 00m\n\
+             \nWeekly Summary:\n===============\nWeek of 2025-09-01 - 2025-09-07: 24h 00m [16h 00m short]\n\
+             \nYearly Summary:\n===============\n2025: 24h 00m of 40h 00m expected [16h 00m short], 1 leave day, 8h 00m per worked day\n\
+             \nWarnings:\n=========\n2025-09-05.md: unreadable\n1 file skipped; run with --strict to fail instead\n"
+        );
+    }
+
+    #[test]
+    fn test_duration_format() {
+        let decimal = DurationFormat { style: crate::duration::DurationStyle::Decimal, precision: 1 };
+        let text = render(&report_in(&["--yearly"], decimal), ReportFormat::Text, None);
+        assert!(text.contains("2025-09-04 Thu - 8.0h (3.0h gap after 12:00)\n"));
+        assert!(text.contains("Week of 2025-09-01 - 2025-09-07: 24.0h [16.0h short]\n"));
+        assert!(text.contains("2025: 24.0h of 40.0h expected [16.0h short], 1 leave day, 8.0h per worked day\n"));
+    }
+
+    #[test]
+    fn test_bars() {
+        let ascii = BarStyle { columns: 80, unicode: false };
+        let hours = Duration::hours;
+        assert_eq!(ascii.bar(hours(4), hours(8), hours(8), 11), "#####.....|");
+        assert_eq!(ascii.bar(hours(10), hours(8), hours(10), 11), "########|#");
+        assert_eq!(ascii.bar(hours(3), Duration::zero(), hours(10), 11), "###");
+        let unicode = BarStyle { unicode: true, ..ascii };
+        assert_eq!(unicode.bar(Duration::minutes(72), hours(8), hours(8), 11), "█▌········│");
+
+        let text = render(&report(&["--bars"]), ReportFormat::Text, Some(BarStyle { columns: 40, unicode: false }));
+        assert!(text.contains(
+            "2025-09-01 Mon - 8h 00m   #########  (holiday: Labor <Day>)\n\
+             2025-09-02 Tue - 8h 00m   #########|\n\
+             2025-09-03 Wed - no file  .........|\n\
+             2025-09-04 Thu - 8h 00m   #########| (3h 00m gap after 12:00)\n"
+        ));
+        assert!(text.contains("Week of 2025-09-01 - 2025-09-07: 24h 00m  #####....| [16h 00m short]\n"));
+        assert!(text.contains("September 2025: 24h 00m\n"));
+    }
+
+    #[test]
+    fn test_markdown_and_html_reports() {
+        let report = report(&["--weekly-hours", "20"]);
+        let markdown = render(&report, ReportFormat::Markdown, None);
+        assert!(markdown.starts_with("# Timesheet Report\n\nGenerated 2025-09-05\n\n## Daily Summary (Last 2 Weeks)\n\n| Date | Day | Hours | Notes |\n| --- | --- | --- | --- |\n"));
+        assert!(markdown.contains("| **2025-09-03** | **Wed** |  | **no file** |\n"));
+        assert!(markdown.contains("## Weekly Summary\n\n| Week | Hours | Target | Short |\n| --- | --- | --- | --- |\n| Week of 2025-09-01 - 2025-09-07 | 24h 00m | 20h 00m |  |\n"));
+
+        let html = render(&report, ReportFormat::Html, None);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<td>holiday: Labor &lt;Day&gt;</td>"));
+        assert!(html.contains("<tr class=\"short\"><td>2025-09-03</td>"));
+        assert!(!html.contains("<link") && !html.contains("<script"));
+
+        let html = render(&self::report(&[]), ReportFormat::Html, None);
+        assert!(html.contains("<tr class=\"short\"><td>Week of 2025-09-01 - 2025-09-07</td><td>24h 00m</td><td>40h 00m</td><td>16h 00m</td></tr>"));
+    }
+}
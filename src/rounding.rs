@@ -0,0 +1,98 @@
+use serde::Deserialize;
+use std::num::NonZeroU32;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RoundingMode {
+    /// Round to the closest increment, halves going up
+    #[default]
+    Nearest,
+    Up,
+    Down,
+}
+
+/// Which total the increment is applied to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RoundingScope {
+    /// Every session and "Work time" entry on its own
+    #[default]
+    Session,
+    /// The time for each project on each day
+    Day,
+    /// The time for each project over the whole billed period
+    Period,
+}
+
+/// A billing rule such as "round each session up to the next 15 minutes"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RoundingRule {
+    #[serde(default)]
+    pub mode: RoundingMode,
+    /// Increment in minutes, e.g. 6 for tenths of an hour or 15 for quarter hours
+    pub increment: NonZeroU32,
+    #[serde(default)]
+    pub scope: RoundingScope,
+}
+
+impl RoundingRule {
+    /// Round a number of minutes to a multiple of the increment
+    pub fn round(self, minutes: i64) -> i64 {
+        let increment = i64::from(self.increment.get());
+        let remainder = minutes.rem_euclid(increment);
+        let down = minutes - remainder;
+        let round_up = match self.mode {
+            RoundingMode::Nearest => remainder * 2 >= increment,
+            RoundingMode::Up => remainder > 0,
+            RoundingMode::Down => false,
+        };
+        if round_up {
+            down + increment
+        } else {
+            down
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(mode: RoundingMode, increment: u32) -> RoundingRule {
+        RoundingRule {
+            mode,
+            increment: NonZeroU32::new(increment).unwrap(),
+            scope: RoundingScope::Session,
+        }
+    }
+
+    #[test]
+    fn test_round() {
+        let cases = [
+            (RoundingMode::Nearest, 15, [(0, 0), (7, 0), (8, 15), (52, 45), (53, 60), (60, 60)]),
+            (RoundingMode::Up, 15, [(0, 0), (1, 15), (15, 15), (16, 30), (59, 60), (60, 60)]),
+            (RoundingMode::Down, 6, [(0, 0), (5, 0), (6, 6), (11, 6), (59, 54), (60, 60)]),
+        ];
+        for (mode, increment, expectations) in cases {
+            for (minutes, expected) in expectations {
+                assert_eq!(rule(mode, increment).round(minutes), expected, "{mode:?} {increment} {minutes}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        let rule: RoundingRule = toml::from_str("mode = \"up\"\nincrement = 15\nscope = \"day\"").unwrap();
+        assert_eq!(rule.mode, RoundingMode::Up);
+        assert_eq!(rule.increment.get(), 15);
+        assert_eq!(rule.scope, RoundingScope::Day);
+
+        let rule: RoundingRule = toml::from_str("increment = 6").unwrap();
+        assert_eq!(rule.mode, RoundingMode::Nearest);
+        assert_eq!(rule.scope, RoundingScope::Session);
+
+        assert!(toml::from_str::<RoundingRule>("increment = 0").is_err());
+        assert!(toml::from_str::<RoundingRule>("mode = \"up\"").is_err());
+    }
+}
@@ -0,0 +1,422 @@
+use crate::cli::ServeArgs;
+use crate::config::Config;
+use crate::coverage::{self, CoverageIssue};
+use crate::duration::DurationFormat;
+use crate::error::TimesheetError;
+use crate::{get_month_name, hours_to_duration, scan_with_holidays, DaySummary, TimesheetParser};
+use chrono::{Duration, Local, NaiveDate};
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::Path;
+use std::time::Instant;
+use tiny_http::{Header, Method, Response, Server};
+
+/// The dashboard page, with its styles and scripts inline so it works offline
+const DASHBOARD: &str = include_str!("dashboard.html");
+
+/// Rescan at least this often, even without changes, so today's running session keeps counting
+const MAX_AGE: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// A total in minutes, decimal hours and the configured duration style, as in `export json`
+#[derive(Debug, Serialize)]
+struct Amount {
+    minutes: i64,
+    hours: f64,
+    duration: String,
+}
+
+impl Amount {
+    fn new(duration: Duration, format: DurationFormat) -> Self {
+        Self {
+            minutes: duration.num_minutes(),
+            hours: format.hours(duration).parse().unwrap_or_default(),
+            duration: format.format(duration),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DayRecord {
+    date: NaiveDate,
+    weekday: String,
+    #[serde(flatten)]
+    total: Amount,
+    leave_minutes: i64,
+    tentative: bool,
+    incomplete: bool,
+    implausible: bool,
+    holiday: Option<String>,
+    /// Number of diagnostics and missing-day findings
+    problems: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct WeekRecord {
+    week_start: NaiveDate,
+    week_end: NaiveDate,
+    label: String,
+    #[serde(flatten)]
+    total: Amount,
+    target_minutes: i64,
+    shortage_minutes: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct MonthRecord {
+    year: i32,
+    month: u32,
+    name: &'static str,
+    #[serde(flatten)]
+    total: Amount,
+}
+
+#[derive(Debug, Serialize)]
+struct ProjectRecord {
+    /// The project tag, or `null` for untagged time
+    project: Option<String>,
+    #[serde(flatten)]
+    total: Amount,
+}
+
+#[derive(Debug, Serialize)]
+struct DiagnosticRecord {
+    date: Option<NaiveDate>,
+    file: Option<String>,
+    line: Option<usize>,
+    message: String,
+}
+
+/// The inclusive `from` and `to` query parameters shared by every endpoint
+#[derive(Debug, Default, PartialEq)]
+struct Range {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+}
+
+impl Range {
+    fn parse(query: &str) -> Result<Self, String> {
+        let mut range = Self::default();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let date = || value.parse::<NaiveDate>().map_err(|_| format!("{key}: expected YYYY-MM-DD, got \"{value}\""));
+            match key {
+                "from" => range.from = Some(date()?),
+                "to" => range.to = Some(date()?),
+                _ => return Err(format!("unknown parameter \"{key}\"")),
+            }
+        }
+        Ok(range)
+    }
+
+    fn contains(&self, date: NaiveDate) -> bool {
+        self.from.is_none_or(|from| date >= from) && self.to.is_none_or(|to| date <= to)
+    }
+}
+
+/// A response body with its status and content type
+#[derive(Debug)]
+struct Reply {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Reply {
+    fn json<T: Serialize>(value: &T) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/json",
+            body: serde_json::to_string_pretty(value).expect("API records always serialize"),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self { status, ..Self::json(&serde_json::json!({ "error": message })) }
+    }
+}
+
+/// Names, sizes and modification times of everything in the directory, so
+/// that edits, new files and deletions all change it
+fn fingerprint(directory: &Path) -> Result<u64, TimesheetError> {
+    let read_error = |error| TimesheetError::io(directory, error);
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(directory).map_err(read_error)? {
+        let entry = entry.map_err(read_error)?;
+        let metadata = entry.metadata().ok();
+        entries.push((entry.file_name(), metadata.as_ref().map(fs::Metadata::len), metadata.and_then(|metadata| metadata.modified().ok())));
+    }
+    entries.sort_unstable();
+
+    let mut hasher = DefaultHasher::new();
+    entries.hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+/// One scan of the directory, served until the files change
+struct Snapshot {
+    /// Increases with every rescan so the dashboard knows to reload
+    version: u64,
+    fingerprint: u64,
+    loaded: Instant,
+    today: NaiveDate,
+    summaries: Vec<DaySummary>,
+    issues: Vec<CoverageIssue>,
+    errors: Vec<String>,
+}
+
+impl Snapshot {
+    fn load(args: &ServeArgs, config: &Config, version: u64) -> Result<Self, TimesheetError> {
+        let fingerprint = fingerprint(&args.common.directory)?;
+        let today = Local::now().date_naive();
+        let (scan, holidays) = scan_with_holidays(&args.common, config, today)?;
+        let issues = coverage::find_issues(&scan.summaries, &holidays, &config.schedule, today);
+        Ok(Self {
+            version,
+            fingerprint,
+            loaded: Instant::now(),
+            today,
+            summaries: scan.summaries,
+            issues,
+            errors: scan.errors.iter().map(ToString::to_string).collect(),
+        })
+    }
+
+    fn is_stale(&self, directory: &Path) -> bool {
+        self.loaded.elapsed() > MAX_AGE
+            || self.today != Local::now().date_naive()
+            || fingerprint(directory).is_ok_and(|fingerprint| fingerprint != self.fingerprint)
+    }
+
+    fn days(&self, range: &Range, format: DurationFormat) -> Vec<DayRecord> {
+        self.summaries
+            .iter()
+            .filter(|day| range.contains(day.date))
+            .map(|day| DayRecord {
+                date: day.date,
+                weekday: day.date.format("%a").to_string(),
+                total: Amount::new(day.total_duration, format),
+                leave_minutes: day.leave_duration.num_minutes(),
+                tentative: day.has_tentative,
+                incomplete: day.has_incomplete,
+                implausible: day.is_implausible(),
+                holiday: day.holiday.clone(),
+                problems: day.diagnostics.len() + self.issues.iter().filter(|issue| issue.date == day.date).count(),
+            })
+            .collect()
+    }
+
+    fn weeks(&self, range: &Range, args: &ServeArgs, format: DurationFormat) -> Vec<WeekRecord> {
+        let days: Vec<_> = self.summaries.iter().filter(|day| range.contains(day.date)).cloned().collect();
+        let target = hours_to_duration(args.target.weekly_hours);
+        TimesheetParser::group_by_week(&days, args.week.week_start)
+            .into_iter()
+            .map(|week| WeekRecord {
+                week_start: week.week_start,
+                week_end: week.week_end(),
+                label: week.label(args.week.iso_weeks),
+                total: Amount::new(week.total_duration, format),
+                target_minutes: target.num_minutes(),
+                shortage_minutes: (target - week.total_duration).num_minutes().max(0),
+            })
+            .collect()
+    }
+
+    fn months(&self, range: &Range, format: DurationFormat) -> Vec<MonthRecord> {
+        let days: Vec<_> = self.summaries.iter().filter(|day| range.contains(day.date)).cloned().collect();
+        TimesheetParser::group_by_month(&days)
+            .into_iter()
+            .map(|month| MonthRecord {
+                year: month.year,
+                month: month.month,
+                name: get_month_name(month.month),
+                total: Amount::new(month.total_duration, format),
+            })
+            .collect()
+    }
+
+    /// Time per project tag, largest first; leave has no project and is left out
+    fn projects(&self, range: &Range, format: DurationFormat) -> Vec<ProjectRecord> {
+        let mut totals: HashMap<Option<&str>, Duration> = HashMap::new();
+        for day in self.summaries.iter().filter(|day| range.contains(day.date)) {
+            for entry in &day.entries {
+                *totals.entry(entry.project()).or_default() += entry.duration().unwrap_or_default();
+            }
+            for entry in &day.work_time_entries {
+                *totals.entry(entry.project()).or_default() += entry.duration;
+            }
+        }
+
+        let mut totals: Vec<_> = totals.into_iter().filter(|(_, total)| *total > Duration::zero()).collect();
+        totals.sort_unstable_by(|(a_project, a_total), (b_project, b_total)| b_total.cmp(a_total).then(a_project.cmp(b_project)));
+        totals
+            .into_iter()
+            .map(|(project, total)| ProjectRecord { project: project.map(str::to_string), total: Amount::new(total, format) })
+            .collect()
+    }
+
+    /// Unreadable files, then entry problems and missing days by date and line
+    fn diagnostics(&self, range: &Range, format: DurationFormat) -> Vec<DiagnosticRecord> {
+        let mut problems: Vec<_> = self
+            .summaries
+            .iter()
+            .filter(|day| range.contains(day.date))
+            .flat_map(|day| day.diagnostics.iter().map(|diagnostic| (day.date, Some(diagnostic.line), diagnostic.kind.describe(format))))
+            .collect();
+        problems.extend(
+            self.issues
+                .iter()
+                .filter(|issue| range.contains(issue.date))
+                .map(|issue| (issue.date, issue.finding.line(), issue.finding.describe(format))),
+        );
+        problems.sort_by_key(|(date, line, _)| (*date, *line));
+
+        let errors = self.errors.iter().map(|message| DiagnosticRecord { date: None, file: None, line: None, message: message.clone() });
+        errors
+            .chain(problems.into_iter().map(|(date, line, message)| DiagnosticRecord {
+                date: Some(date),
+                file: Some(format!("{date}.md")),
+                line,
+                message,
+            }))
+            .collect()
+    }
+}
+
+/// Answer a GET request for `url`, a path with an optional query string
+fn route(snapshot: &Snapshot, args: &ServeArgs, format: DurationFormat, url: &str) -> Reply {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    if path == "/" {
+        return Reply { status: 200, content_type: "text/html; charset=utf-8", body: DASHBOARD.to_string() };
+    }
+    let range = match Range::parse(query) {
+        Ok(range) => range,
+        Err(message) => return Reply::error(400, &message),
+    };
+
+    match path {
+        "/api/version" => Reply::json(&serde_json::json!({ "version": snapshot.version })),
+        "/api/days" => Reply::json(&snapshot.days(&range, format)),
+        "/api/weeks" => Reply::json(&snapshot.weeks(&range, args, format)),
+        "/api/months" => Reply::json(&snapshot.months(&range, format)),
+        "/api/projects" => Reply::json(&snapshot.projects(&range, format)),
+        "/api/diagnostics" => Reply::json(&snapshot.diagnostics(&range, format)),
+        _ => Reply::error(404, &format!("no such endpoint {path}")),
+    }
+}
+
+/// Serve the JSON API and the dashboard until interrupted, rescanning the
+/// directory before a request whenever its files have changed
+pub fn run(args: &ServeArgs, config: &Config, format: DurationFormat) -> Result<(), TimesheetError> {
+    let mut snapshot = Snapshot::load(args, config, 1)?;
+    let server = Server::http(args.bind)
+        .map_err(|error| TimesheetError::io(Path::new(&args.bind.to_string()), io::Error::other(error)))?;
+    println!("Serving {} on http://{}/", args.common.directory.display(), args.bind);
+
+    for request in server.incoming_requests() {
+        if snapshot.is_stale(&args.common.directory) {
+            match Snapshot::load(args, config, snapshot.version + 1) {
+                Ok(rescanned) => snapshot = rescanned,
+                Err(error) => eprintln!("Error: {error}"),
+            }
+        }
+
+        let reply = if *request.method() == Method::Get {
+            route(&snapshot, args, format, request.url())
+        } else {
+            Reply::error(405, "only GET requests are supported")
+        };
+        let content_type = Header::from_bytes("Content-Type", reply.content_type).expect("content types are valid headers");
+        let response = Response::from_string(reply.body).with_status_code(reply.status).with_header(content_type);
+        if let Err(error) = request.respond(response) {
+            eprintln!("Error: {error}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Command};
+    use crate::coverage::Finding;
+    use clap::Parser;
+
+    fn args() -> ServeArgs {
+        match Cli::parse_from(["markdown_timesheet", "serve", "--weekly-hours", "10"]).into_command() {
+            Command::Serve(args) => args,
+            command => panic!("unexpected command {command:?}"),
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 9, day).unwrap()
+    }
+
+    fn snapshot() -> Snapshot {
+        let parser = TimesheetParser::new(false).unwrap();
+        Snapshot {
+            version: 3,
+            fingerprint: 0,
+            loaded: Instant::now(),
+            today: date(9),
+            summaries: vec![
+                parser.parse_file("Start work 9:00 #acme\nStop work 12:00\nWork time 1 hour", date(1)).unwrap(),
+                parser.parse_file("Start work 9:00 #beta\nStop work 10:00\nStop work 11:00", date(3)).unwrap(),
+                parser.parse_file("Work time 4 hours #acme", date(8)).unwrap(),
+            ],
+            issues: vec![CoverageIssue { date: date(2), finding: Finding::MissingFile }],
+            errors: vec!["2025-09-04.md: unreadable".to_string()],
+        }
+    }
+
+    fn get(url: &str) -> (u16, serde_json::Value) {
+        let reply = route(&snapshot(), &args(), DurationFormat::default(), url);
+        (reply.status, serde_json::from_str(&reply.body).unwrap())
+    }
+
+    #[test]
+    fn test_api() {
+        let (status, days) = get("/api/days?from=2025-09-02&to=2025-09-07");
+        assert_eq!(status, 200);
+        assert_eq!(days.as_array().unwrap().len(), 1);
+        assert_eq!(days[0]["date"], "2025-09-03");
+        assert_eq!(days[0]["minutes"], 60);
+        assert_eq!(days[0]["problems"], 1);
+
+        let (_, weeks) = get("/api/weeks");
+        assert_eq!(weeks[0]["label"], "Week of 2025-09-01 - 2025-09-07");
+        assert_eq!(weeks[0]["duration"], "5h 00m");
+        assert_eq!(weeks[0]["shortage_minutes"], 300);
+        assert_eq!(weeks[1]["shortage_minutes"], 360);
+
+        let (_, months) = get("/api/months?to=2025-09-05");
+        assert_eq!(months[0]["name"], "September");
+        assert_eq!(months[0]["hours"], 5.0);
+
+        let (_, projects) = get("/api/projects");
+        let projects: Vec<_> = projects.as_array().unwrap().iter().map(|project| (project["project"].clone(), project["minutes"].clone())).collect();
+        assert_eq!(projects, [("acme".into(), 420.into()), (serde_json::Value::Null, 60.into()), ("beta".into(), 60.into())]);
+
+        let (_, diagnostics) = get("/api/diagnostics");
+        assert_eq!(diagnostics[0]["message"], "2025-09-04.md: unreadable");
+        assert_eq!(diagnostics[1]["file"], "2025-09-02.md");
+        assert_eq!(diagnostics[2]["line"], 3);
+
+        assert_eq!(get("/api/version").1["version"], 3);
+    }
+
+    #[test]
+    fn test_bad_requests() {
+        assert_eq!(get("/api/days?from=yesterday"), (400, serde_json::json!({ "error": "from: expected YYYY-MM-DD, got \"yesterday\"" })));
+        assert_eq!(get("/api/days?last=7").0, 400);
+        assert_eq!(get("/api/hours").0, 404);
+
+        let page = route(&snapshot(), &args(), DurationFormat::default(), "/");
+        assert!(page.content_type.starts_with("text/html"));
+        assert!(!page.body.contains("<script src") && !page.body.contains("<link"));
+    }
+}
@@ -0,0 +1,228 @@
+use crate::error::TimesheetError;
+use crate::import::{split_tags, whole_minutes, ImportedSession};
+use crate::DaySummary;
+use chrono::{DateTime, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Timestamp format used by `timew export`, always in UTC
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// One tracked interval in `timew export` JSON
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct Interval {
+    #[serde(serialize_with = "serialize_timestamp", deserialize_with = "deserialize_timestamp")]
+    start: DateTime<Utc>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_timestamp",
+        deserialize_with = "deserialize_optional_timestamp"
+    )]
+    end: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    annotation: Option<String>,
+}
+
+fn serialize_timestamp<S: Serializer>(timestamp: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&timestamp.format(TIMESTAMP_FORMAT))
+}
+
+#[allow(clippy::ref_option)] // serde's `serialize_with` signature
+fn serialize_optional_timestamp<S: Serializer>(timestamp: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error> {
+    match timestamp {
+        Some(timestamp) => serialize_timestamp(timestamp, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn deserialize_timestamp<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    NaiveDateTime::parse_from_str(&text, TIMESTAMP_FORMAT)
+        .map(|timestamp| timestamp.and_utc())
+        .map_err(|_| serde::de::Error::custom(format!("invalid Timewarrior timestamp \"{text}\"")))
+}
+
+fn deserialize_optional_timestamp<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error> {
+    deserialize_timestamp(deserializer).map(Some)
+}
+
+pub fn parse_export(json: &str) -> Result<Vec<Interval>, TimesheetError> {
+    serde_json::from_str(json).map_err(|error| TimesheetError::parse(error.line(), error.to_string()))
+}
+
+/// Sessions in the local time zone `tz`. Intervals that run past midnight
+/// are split into one session per day, the first ending at "0:00"; a day
+/// covered from midnight to midnight cannot be written and is an error.
+/// Tags that cannot be written as `#tags` are kept as words in the description,
+/// and seconds are dropped.
+pub fn to_sessions<Tz: TimeZone>(intervals: &[Interval], tz: &Tz) -> Result<Vec<ImportedSession>, TimesheetError> {
+    let mut sessions = Vec::new();
+    for interval in intervals {
+        let start = whole_minutes(interval.start.with_timezone(tz).naive_local());
+        let end = interval.end.as_ref().map(|end| whole_minutes(end.with_timezone(tz).naive_local()));
+        let (tags, words) = split_tags(interval.tags.iter().map(String::as_str));
+        let description = words
+            .into_iter()
+            .chain(interval.annotation.as_deref().map(str::trim))
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut from = start;
+        loop {
+            let midnight = (from.date() + chrono::Days::new(1)).and_time(NaiveTime::MIN);
+            let until = end.map(|end| end.min(midnight));
+            if from.time() == NaiveTime::MIN && until == Some(midnight) {
+                return Err(TimesheetError::Usage(format!(
+                    "interval from {} to {} covers all of {}, which a daily file cannot hold",
+                    start.format("%Y-%m-%d %H:%M"),
+                    end.unwrap_or(midnight).format("%Y-%m-%d %H:%M"),
+                    from.date()
+                )));
+            }
+            sessions.push(ImportedSession {
+                date: from.date(),
+                start: from.time(),
+                end: until.map(|until| until.time()),
+                tags: tags.clone(),
+                description: description.clone(),
+            });
+            match end {
+                Some(end) if end > midnight => from = midnight,
+                _ => break,
+            }
+        }
+    }
+    Ok(sessions)
+}
+
+/// Every session with a start time as a Timewarrior interval. Today's running
+/// session is exported as an open interval; sessions that were never stopped
+/// and "Work time" entries, which have no start time, are left out.
+pub fn from_summaries<Tz: TimeZone>(summaries: &[DaySummary], tz: &Tz) -> Vec<Interval> {
+    let to_utc = |naive: NaiveDateTime| tz.from_local_datetime(&naive).earliest().map(|time| time.with_timezone(&Utc));
+
+    summaries
+        .iter()
+        .flat_map(|day| day.entries.iter().map(move |entry| (day.date, entry)))
+        .filter_map(|(date, entry)| {
+            let start = date.and_time(entry.start_time?);
+            let end = match (entry.tentative, entry.duration()) {
+                (true, _) => None,
+                (false, Some(duration)) => Some(to_utc(start + duration)?),
+                (false, None) => return None,
+            };
+            Some(Interval {
+                start: to_utc(start)?,
+                end,
+                tags: entry.tags.clone(),
+                annotation: Some(entry.description.clone()).filter(|description| !description.is_empty()),
+            })
+        })
+        .collect()
+}
+
+pub fn to_json(intervals: &[Interval]) -> String {
+    serde_json::to_string_pretty(intervals).expect("intervals always serialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TimesheetParser;
+    use chrono::{Duration, FixedOffset, NaiveDate};
+
+    const EXPORT: &str = r#"[
+{"id":2,"start":"20250825T130000Z","end":"20250825T163000Z","tags":["Acme","client work","2025"],"annotation":"design review"},
+{"id":1,"start":"20250825T220000Z","tags":["acme"]}
+]"#;
+
+    #[test]
+    fn test_import_intervals() {
+        let intervals = parse_export(EXPORT).unwrap();
+        assert_eq!(intervals[0].end.unwrap() - intervals[0].start, Duration::minutes(210));
+
+        // UTC-4, so the late interval starts at 18:00 local time
+        let tz = FixedOffset::west_opt(4 * 3600).unwrap();
+        let sessions = to_sessions(&intervals, &tz).unwrap();
+        assert_eq!(sessions[0].date, NaiveDate::from_ymd_opt(2025, 8, 25).unwrap());
+        assert_eq!(sessions[0].start.to_string(), "09:00:00");
+        assert_eq!(sessions[0].end.map(|end| end.to_string()).as_deref(), Some("12:30:00"));
+        assert_eq!(sessions[0].tags, ["acme", "client-work"]);
+        assert_eq!(sessions[0].description, "2025 design review");
+        assert_eq!(sessions[1].start.to_string(), "18:00:00");
+        assert_eq!(sessions[1].end, None);
+    }
+
+    #[test]
+    fn test_invalid_export() {
+        let error = parse_export("[\n{\"start\":\"2025-08-25 09:00\"}\n]").unwrap_err();
+        assert!(matches!(error, TimesheetError::Parse { line: 2, .. }));
+        assert!(error.to_string().contains("invalid Timewarrior timestamp"));
+    }
+
+    #[test]
+    fn test_export_roundtrip() {
+        let parser = TimesheetParser::new(false).unwrap();
+        let date = NaiveDate::from_ymd_opt(2025, 8, 25).unwrap();
+        let day = parser
+            .parse_file("Start work 9:00 #acme design review\nStop work 12:30\nStart work 22:00\nStop work 1:00\nStart work 14:00", date)
+            .unwrap();
+        let tz = FixedOffset::east_opt(2 * 3600).unwrap();
+
+        let intervals = from_summaries(&[day], &tz);
+        assert_eq!(intervals.len(), 2);
+        let json: serde_json::Value = serde_json::from_str(&to_json(&intervals)).unwrap();
+        assert_eq!(json[0]["start"], "20250825T070000Z");
+        assert_eq!(json[0]["end"], "20250825T103000Z");
+        assert_eq!(json[0]["tags"][0], "acme");
+        assert_eq!(json[0]["annotation"], "design review");
+        assert_eq!(json[1]["end"], "20250825T230000Z");
+        assert!(json[1].get("tags").is_none());
+
+        let sessions = to_sessions(&parse_export(&to_json(&intervals)).unwrap(), &tz).unwrap();
+        assert_eq!(sessions[0].start.to_string(), "09:00:00");
+        assert_eq!(sessions[1].end.map(|end| end.to_string()).as_deref(), Some("00:00:00"));
+        assert_eq!(sessions[2].date, date.succ_opt().unwrap());
+        assert_eq!((sessions[2].start.to_string(), sessions[2].end.unwrap().to_string()), ("00:00:00".to_string(), "01:00:00".to_string()));
+    }
+
+    #[test]
+    fn test_reimport_with_seconds() {
+        let directory = std::env::temp_dir().join(format!("markdown_timesheet_timewarrior_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let export = r#"[{"start":"20250825T130012Z","end":"20250825T163047Z","tags":["acme"]},{"start":"20250825T170059Z","tags":["acme"]}]"#;
+        let sessions = to_sessions(&parse_export(export).unwrap(), &Utc).unwrap();
+        assert_eq!(sessions[0].start.to_string(), "13:00:00");
+        assert_eq!(sessions[0].end.map(|end| end.to_string()).as_deref(), Some("16:30:00"));
+
+        let updates = crate::import::plan_merge(&directory, &sessions).unwrap();
+        assert_eq!(updates[0].updated, "Start work 13:00 #acme\nStop work 16:30\nStart work 17:00 #acme\n");
+        crate::import::write_updates(&updates).unwrap();
+        assert!(crate::import::plan_merge(&directory, &sessions).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_split_at_midnight() {
+        let export = r#"[{"start":"20250825T200000Z","end":"20250827T020000Z","tags":["acme"]}]"#;
+        let error = to_sessions(&parse_export(export).unwrap(), &Utc).unwrap_err();
+        assert_eq!(error.to_string(), "interval from 2025-08-25 20:00 to 2025-08-27 02:00 covers all of 2025-08-26, which a daily file cannot hold");
+
+        let export = r#"[{"start":"20250825T200000Z","end":"20250826T023000Z","tags":["acme"]}]"#;
+        let sessions = to_sessions(&parse_export(export).unwrap(), &Utc).unwrap();
+        let parser = TimesheetParser::new(false).unwrap();
+        let total: Duration = sessions
+            .iter()
+            .map(|session| {
+                let lines = format!("Start work {}\nStop work {}", session.start.format("%H:%M"), session.end.unwrap().format("%H:%M"));
+                parser.parse_file(&lines, session.date).unwrap().total_duration
+            })
+            .sum();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(total, Duration::minutes(390));
+    }
+}
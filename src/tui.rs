@@ -0,0 +1,546 @@
+use crate::cli::TuiArgs;
+use crate::config::Config;
+use crate::coverage::{self, CoverageIssue};
+use crate::duration::DurationFormat;
+use crate::error::TimesheetError;
+use crate::{format_duration_with_flags, get_month_name, hours_to_duration, scan_with_holidays};
+use crate::{DaySummary, TimesheetParser, WeekSummary};
+use chrono::{Datelike, Duration, Local, Months, NaiveDate, NaiveTime};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{enable_raw_mode, EnterAlternateScreen};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::process;
+use std::time::Instant;
+
+/// Calendar cells are four columns wide: a space, the day and a status mark
+const CALENDAR_WIDTH: u16 = 7 * 4 + 2;
+/// Weekday header, up to six weeks, the legend and the border
+const CALENDAR_HEIGHT: u16 = 1 + 6 + 1 + 2;
+/// Background of the heatmap levels, from nothing worked to the daily target reached
+const HEAT_COLORS: [Color; 5] = [Color::Reset, Color::Indexed(22), Color::Indexed(28), Color::Indexed(34), Color::Indexed(40)];
+
+/// Heatmap level of a day: 0 when nothing was worked, 1 to 3 for thirds of
+/// the daily target and 4 once it is reached
+pub fn heat_level(worked: Duration, target: Duration) -> usize {
+    if worked <= Duration::zero() {
+        return 0;
+    }
+    if worked >= target || target <= Duration::zero() {
+        return 4;
+    }
+    usize::try_from(worked.num_minutes() * 3 / target.num_minutes().max(1)).map_or(1, |third| third + 1)
+}
+
+/// A row of the day pane: a session, entry or problem and the line it is on
+#[derive(Debug, PartialEq)]
+pub struct DayItem {
+    pub line: Option<usize>,
+    pub text: String,
+    pub problem: bool,
+}
+
+/// Entry text followed by its project and description, where it has them
+fn with_details(mut text: String, project: Option<&str>, description: &str) -> String {
+    if let Some(project) = project {
+        let _ = write!(text, "  #{project}");
+    }
+    if !description.is_empty() {
+        let _ = write!(text, "  {description}");
+    }
+    text
+}
+
+/// Sessions, entries and problems of one day in the order of their lines.
+/// Holiday and leave credit have no single line and come first.
+pub fn day_items(date: NaiveDate, day: Option<&DaySummary>, issues: &[CoverageIssue], durations: DurationFormat) -> Vec<DayItem> {
+    let mut items = Vec::new();
+    if let Some(day) = day {
+        if let Some(name) = &day.holiday {
+            items.push(DayItem { line: None, text: format!("Holiday: {name}"), problem: false });
+        }
+        if day.leave_duration > Duration::zero() {
+            items.push(DayItem { line: None, text: format!("Leave  {}", durations.format(day.leave_duration)), problem: false });
+        }
+        for entry in &day.entries {
+            let time = |time: Option<NaiveTime>| time.map_or_else(|| "--:--".to_string(), |time| time.format("%H:%M").to_string());
+            let duration = entry.duration().map_or_else(
+                || "E!".to_string(),
+                |duration| format_duration_with_flags(durations, duration, entry.tentative, false, false),
+            );
+            let text = format!("{}-{}  {duration}", time(entry.start_time), time(entry.end_time));
+            items.push(DayItem { line: Some(entry.line), text: with_details(text, entry.project(), &entry.description), problem: false });
+        }
+        for entry in &day.work_time_entries {
+            let text = format!("Work time  {}", durations.format(entry.duration));
+            items.push(DayItem { line: Some(entry.line), text: with_details(text, entry.project(), &entry.description), problem: false });
+        }
+        for diagnostic in &day.diagnostics {
+            items.push(DayItem { line: Some(diagnostic.line), text: diagnostic.kind.describe(durations), problem: true });
+        }
+    }
+    for issue in issues.iter().filter(|issue| issue.date == date) {
+        items.push(DayItem { line: issue.finding.line(), text: issue.finding.describe(durations), problem: true });
+    }
+    items.sort_by_key(|item| item.line);
+    items
+}
+
+/// The command that opens `path` in `editor`, at `line` when there is one.
+/// `editor` may carry arguments, e.g. "code --wait".
+pub fn editor_command(editor: &str, path: &Path, line: Option<usize>) -> Option<process::Command> {
+    let mut words = editor.split_whitespace();
+    let mut command = process::Command::new(words.next()?);
+    command.args(words);
+    if let Some(line) = line {
+        command.arg(format!("+{line}"));
+    }
+    command.arg(path);
+    Some(command)
+}
+
+/// Start of every week shown in the calendar for the month containing `date`
+fn calendar_weeks(date: NaiveDate, week_start: chrono::Weekday) -> Vec<NaiveDate> {
+    let first = date.with_day(1).unwrap_or(date);
+    let next_month = first + Months::new(1);
+    let mut starts = Vec::new();
+    let mut start = first.week(week_start).first_day();
+    while start < next_month {
+        starts.push(start);
+        start += Duration::days(7);
+    }
+    starts
+}
+
+/// Everything read from the directory in one scan
+struct Snapshot {
+    today: NaiveDate,
+    summaries: Vec<DaySummary>,
+    issues: Vec<CoverageIssue>,
+    skipped: usize,
+    loaded_at: NaiveTime,
+}
+
+impl Snapshot {
+    /// Scan the directory as `report` does; today's open session is counted up to now
+    fn load(args: &TuiArgs, config: &Config) -> Result<Self, TimesheetError> {
+        let now = Local::now();
+        let today = now.date_naive();
+        let (scan, holidays) = scan_with_holidays(&args.common, config, today)?;
+        let issues = coverage::find_issues(&scan.summaries, &holidays, &config.schedule, today);
+        Ok(Self {
+            today,
+            summaries: scan.summaries,
+            issues,
+            skipped: scan.errors.len(),
+            loaded_at: now.time(),
+        })
+    }
+
+    fn day(&self, date: NaiveDate) -> Option<&DaySummary> {
+        let index = self.summaries.binary_search_by_key(&date, |day| day.date).ok()?;
+        self.summaries.get(index)
+    }
+
+    fn has_problems(&self, date: NaiveDate) -> bool {
+        self.day(date).is_some_and(|day| !day.diagnostics.is_empty()) || self.issues.iter().any(|issue| issue.date == date)
+    }
+}
+
+/// What the event loop should do after a key press
+#[derive(Debug, PartialEq)]
+enum Action {
+    None,
+    Quit,
+    Refresh,
+    /// Open the selected day's file, at a line if one is chosen
+    Edit(Option<usize>),
+}
+
+struct App<'a> {
+    args: &'a TuiArgs,
+    config: &'a Config,
+    durations: DurationFormat,
+    snapshot: Snapshot,
+    selected: NaiveDate,
+    /// The highlighted row while drilled into the selected day
+    detail: Option<usize>,
+    /// The last refresh or editor error
+    message: Option<String>,
+}
+
+impl<'a> App<'a> {
+    fn new(args: &'a TuiArgs, config: &'a Config, durations: DurationFormat, snapshot: Snapshot) -> Self {
+        let selected = snapshot.today;
+        Self { args, config, durations, snapshot, selected, detail: None, message: None }
+    }
+
+    /// Rescan the directory, keeping the previous data if that fails
+    fn reload(&mut self) {
+        match Snapshot::load(self.args, self.config) {
+            Ok(snapshot) => {
+                self.snapshot = snapshot;
+                self.message = None;
+            }
+            Err(error) => self.message = Some(error.to_string()),
+        }
+    }
+
+    fn items(&self) -> Vec<DayItem> {
+        day_items(self.selected, self.snapshot.day(self.selected), &self.snapshot.issues, self.durations)
+    }
+
+    fn daily_target(&self) -> Duration {
+        self.config.schedule.daily_target(hours_to_duration(self.args.target.weekly_hours))
+    }
+
+    fn handle_key(&mut self, code: KeyCode) -> Action {
+        if let Some(index) = self.detail {
+            let items = self.items();
+            match code {
+                KeyCode::Up | KeyCode::Char('k') => self.detail = Some(index.saturating_sub(1)),
+                KeyCode::Down | KeyCode::Char('j') => self.detail = Some((index + 1).min(items.len().saturating_sub(1))),
+                KeyCode::Esc | KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') => self.detail = None,
+                KeyCode::Enter | KeyCode::Char('e') => return Action::Edit(items.get(index).and_then(|item| item.line)),
+                KeyCode::Char('r') => return Action::Refresh,
+                KeyCode::Char('q') => return Action::Quit,
+                _ => {}
+            }
+            return Action::None;
+        }
+
+        let selected = self.selected;
+        self.selected = match code {
+            KeyCode::Left | KeyCode::Char('h') => selected - Duration::days(1),
+            KeyCode::Right | KeyCode::Char('l') => selected + Duration::days(1),
+            KeyCode::Up | KeyCode::Char('k') => selected - Duration::days(7),
+            KeyCode::Down | KeyCode::Char('j') => selected + Duration::days(7),
+            KeyCode::PageUp | KeyCode::Char('[') => selected - Months::new(1),
+            KeyCode::PageDown | KeyCode::Char(']') => selected + Months::new(1),
+            KeyCode::Home | KeyCode::Char('t') => self.snapshot.today,
+            KeyCode::Enter => {
+                self.detail = Some(0);
+                selected
+            }
+            KeyCode::Char('e') => return Action::Edit(None),
+            KeyCode::Char('r') => return Action::Refresh,
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            _ => selected,
+        };
+        Action::None
+    }
+
+    /// Suspend the dashboard while `$VISUAL` or `$EDITOR` edits the selected day
+    fn edit(&mut self, terminal: &mut DefaultTerminal, line: Option<usize>) -> Result<(), TimesheetError> {
+        let path = self.args.common.directory.join(format!("{}.md", self.selected));
+        let editor = std::env::var("VISUAL").or_else(|_| std::env::var("EDITOR")).unwrap_or_else(|_| "vi".to_string());
+        let Some(mut command) = editor_command(&editor, &path, line) else {
+            self.message = Some("$EDITOR is empty".to_string());
+            return Ok(());
+        };
+
+        ratatui::restore();
+        let status = command.status();
+        enable_raw_mode().map_err(terminal_error)?;
+        execute!(std::io::stdout(), EnterAlternateScreen).map_err(terminal_error)?;
+        terminal.clear().map_err(terminal_error)?;
+
+        self.reload();
+        match status {
+            Ok(status) if status.success() => {}
+            Ok(status) => self.message = Some(format!("{editor} exited with {status}")),
+            Err(error) => self.message = Some(format!("could not start {editor}: {error}")),
+        }
+        Ok(())
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<(), TimesheetError> {
+        let refresh = std::time::Duration::from_secs(self.args.refresh);
+        let mut last_scan = Instant::now();
+        loop {
+            terminal.draw(|frame| self.draw(frame)).map_err(terminal_error)?;
+
+            if !event::poll(refresh.saturating_sub(last_scan.elapsed())).map_err(terminal_error)? {
+                self.reload();
+                last_scan = Instant::now();
+                continue;
+            }
+            let Event::Key(key) = event::read().map_err(terminal_error)? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match self.handle_key(key.code) {
+                Action::None => {}
+                Action::Quit => return Ok(()),
+                Action::Refresh => {
+                    self.reload();
+                    last_scan = Instant::now();
+                }
+                Action::Edit(line) => {
+                    self.edit(terminal, line)?;
+                    last_scan = Instant::now();
+                }
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [top, day_area, footer] =
+            Layout::vertical([Constraint::Length(CALENDAR_HEIGHT), Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+        let [calendar_area, weeks_area] = Layout::horizontal([Constraint::Length(CALENDAR_WIDTH), Constraint::Min(0)]).areas(top);
+
+        self.draw_calendar(frame, calendar_area);
+        self.draw_weeks(frame, weeks_area);
+        self.draw_day(frame, day_area);
+
+        let help = if self.detail.is_some() {
+            "↑↓ select  ⏎/e edit at line  esc back  r refresh  q quit"
+        } else {
+            "←↓↑→ move  [ ] month  t today  ⏎ day  e edit  r refresh  q quit"
+        };
+        let mut status = vec![format!("updated {}", self.snapshot.loaded_at.format("%H:%M:%S"))];
+        if self.snapshot.skipped > 0 {
+            let noun = if self.snapshot.skipped == 1 { "file" } else { "files" };
+            status.push(format!("{} {noun} skipped", self.snapshot.skipped));
+        }
+        status.extend(self.message.clone());
+        frame.render_widget(Paragraph::new(format!("{help}  |  {}", status.join(", "))).style(Style::new().add_modifier(Modifier::DIM)), footer);
+    }
+
+    fn draw_calendar(&self, frame: &mut Frame, area: Rect) {
+        let week_start = self.args.week.week_start;
+        let target = self.daily_target();
+
+        let mut weekday = week_start;
+        let mut header = String::new();
+        for _ in 0..7 {
+            let _ = write!(header, " {:.2} ", weekday.to_string());
+            weekday = weekday.succ();
+        }
+        let mut lines = vec![Line::styled(header, Style::new().add_modifier(Modifier::BOLD))];
+
+        for start in calendar_weeks(self.selected, week_start) {
+            let cells = (0..7).map(|offset| start + Duration::days(offset)).map(|date| {
+                if date.month() != self.selected.month() {
+                    return Span::raw("    ");
+                }
+                let day = self.snapshot.day(date);
+                let worked = day.map_or(Duration::zero(), |day| day.total_duration);
+                let mark = if self.snapshot.has_problems(date) {
+                    '!'
+                } else if day.is_some_and(|day| day.has_tentative) {
+                    '*'
+                } else {
+                    ' '
+                };
+                let mut style = Style::new().bg(HEAT_COLORS[heat_level(worked, target)]);
+                if date == self.snapshot.today {
+                    style = style.add_modifier(Modifier::UNDERLINED);
+                }
+                if date == self.selected {
+                    style = style.add_modifier(Modifier::REVERSED | Modifier::BOLD);
+                }
+                Span::styled(format!(" {:>2}{mark}", date.day()), style)
+            });
+            lines.push(Line::from(cells.collect::<Vec<_>>()));
+        }
+
+        while lines.len() < 7 {
+            lines.push(Line::default());
+        }
+        let mut legend = vec![Span::raw(" less ")];
+        legend.extend(HEAT_COLORS.iter().map(|color| Span::styled("  ", Style::new().bg(*color))));
+        legend.push(Span::raw(" more"));
+        lines.push(Line::from(legend));
+
+        let title = format!(" {} {} ", get_month_name(self.selected.month()), self.selected.year());
+        frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(title)), area);
+    }
+
+    fn draw_weeks(&self, frame: &mut Frame, area: Rect) {
+        let weeks: HashMap<_, _> = TimesheetParser::group_by_week(&self.snapshot.summaries, self.args.week.week_start)
+            .into_iter()
+            .map(|week| (week.week_start, week))
+            .collect();
+        let target = hours_to_duration(self.args.target.weekly_hours);
+
+        let starts = calendar_weeks(self.selected, self.args.week.week_start);
+        let rows = starts.iter().map(|start| {
+            let empty = WeekSummary { week_start: *start, total_duration: Duration::zero(), days: Vec::new() };
+            let week = weeks.get(start).unwrap_or(&empty);
+            let shortage = target - week.total_duration;
+            let short = if shortage > Duration::zero() {
+                let style = if week.week_end() < self.snapshot.today { Style::new().fg(Color::Red) } else { Style::new().fg(Color::Yellow) };
+                Span::styled(self.durations.format(shortage), style)
+            } else {
+                Span::raw("-")
+            };
+            Row::new(vec![
+                Span::raw(week.label(self.args.week.iso_weeks)),
+                Span::raw(self.durations.format(week.total_duration)),
+                Span::raw(self.durations.format(target)),
+                short,
+            ])
+        });
+
+        let month_total: Duration = self
+            .snapshot
+            .summaries
+            .iter()
+            .filter(|day| day.date.year() == self.selected.year() && day.date.month() == self.selected.month())
+            .map(|day| day.total_duration)
+            .sum();
+        let table = Table::new(rows, [Constraint::Min(12), Constraint::Length(9), Constraint::Length(9), Constraint::Length(9)])
+            .header(Row::new(["Week", "Worked", "Target", "Short"]).style(Style::new().add_modifier(Modifier::BOLD)))
+            .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .block(Block::bordered().title(format!(" Weeks: {} this month ", self.durations.format(month_total))));
+
+        let selected_row = starts.iter().position(|start| self.selected >= *start && self.selected < *start + Duration::days(7));
+        frame.render_stateful_widget(table, area, &mut TableState::default().with_selected(selected_row));
+    }
+
+    fn draw_day(&self, frame: &mut Frame, area: Rect) {
+        let day = self.snapshot.day(self.selected);
+        let total = day.map_or_else(
+            || "no file".to_string(),
+            |day| format_duration_with_flags(self.durations, day.total_duration, day.has_tentative, day.has_incomplete, day.is_implausible()),
+        );
+        let title = format!(" {} {}: {total} ", self.selected.format("%a"), self.selected);
+
+        let mut items: Vec<_> = self
+            .items()
+            .into_iter()
+            .map(|item| {
+                let line = item.line.map_or_else(String::new, |line| line.to_string());
+                let style = if item.problem { Style::new().fg(Color::Red) } else { Style::new() };
+                ListItem::new(format!("{line:>4}  {}", item.text)).style(style)
+            })
+            .collect();
+        if items.is_empty() {
+            items.push(ListItem::new("      No entries").style(Style::new().add_modifier(Modifier::DIM)));
+        }
+
+        let list = List::new(items)
+            .block(Block::bordered().title(title))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut ListState::default().with_selected(self.detail));
+    }
+}
+
+fn terminal_error(error: std::io::Error) -> TimesheetError {
+    TimesheetError::io(Path::new("<terminal>"), error)
+}
+
+/// Browse the timesheets in an interactive dashboard until `q` is pressed
+pub fn run(args: &TuiArgs, config: &Config, durations: DurationFormat) -> Result<(), TimesheetError> {
+    let mut app = App::new(args, config, durations, Snapshot::load(args, config)?);
+    let mut terminal = ratatui::init();
+    let result = app.event_loop(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Command};
+    use crate::coverage::Finding;
+    use clap::Parser;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn args() -> TuiArgs {
+        match Cli::parse_from(["markdown_timesheet", "tui"]).into_command() {
+            Command::Tui(args) => args,
+            command => panic!("unexpected command {command:?}"),
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 9, day).unwrap()
+    }
+
+    fn snapshot() -> Snapshot {
+        let parser = TimesheetParser::new(false).unwrap();
+        let summaries = vec![
+            parser.parse_file("Start work 9:00 #acme planning\nStop work 12:00\nStop work 13:00\nWork time 2 hours review", date(1)).unwrap(),
+            parser.parse_file("Start work 9:00\nStop work 17:00", date(2)).unwrap(),
+        ];
+        let issues = vec![CoverageIssue { date: date(3), finding: Finding::MissingFile }];
+        Snapshot { today: date(4), summaries, issues, skipped: 0, loaded_at: NaiveTime::MIN }
+    }
+
+    #[test]
+    fn test_heat_level() {
+        let target = Duration::hours(8);
+        let levels: Vec<_> = [0, 1, 3, 6, 8, 10].map(|hours| heat_level(Duration::hours(hours), target)).into();
+        assert_eq!(levels, [0, 1, 2, 3, 4, 4]);
+    }
+
+    #[test]
+    fn test_day_items() {
+        let snapshot = snapshot();
+        let items = day_items(date(1), snapshot.day(date(1)), &snapshot.issues, DurationFormat::default());
+        let rows: Vec<_> = items.iter().map(|item| (item.line, item.text.as_str(), item.problem)).collect();
+        assert_eq!(rows, [
+            (Some(1), "09:00-12:00  3h 00m  #acme  planning", false),
+            (Some(3), "stop at 13:00 has no matching start", true),
+            (Some(4), "Work time  2h 00m  review", false),
+        ]);
+
+        let items = day_items(date(3), None, &snapshot.issues, DurationFormat::default());
+        assert_eq!(items, [DayItem { line: None, text: "no file for a scheduled workday".to_string(), problem: true }]);
+    }
+
+    #[test]
+    fn test_navigation_and_editor() {
+        let (args, config) = (args(), Config::default());
+        let mut app = App::new(&args, &config, DurationFormat::default(), snapshot());
+        for code in [KeyCode::Left, KeyCode::Left, KeyCode::Up, KeyCode::Char(']')] {
+            assert_eq!(app.handle_key(code), Action::None);
+        }
+        assert_eq!(app.selected, NaiveDate::from_ymd_opt(2025, 9, 26).unwrap());
+        app.handle_key(KeyCode::Char('t'));
+        assert_eq!(app.selected, date(4));
+
+        app.selected = date(1);
+        app.handle_key(KeyCode::Enter);
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Down);
+        assert_eq!(app.handle_key(KeyCode::Char('e')), Action::Edit(Some(4)));
+        app.handle_key(KeyCode::Esc);
+        assert_eq!(app.handle_key(KeyCode::Char('q')), Action::Quit);
+
+        let command = editor_command("code --wait", Path::new("2025-09-01.md"), Some(4)).unwrap();
+        assert_eq!(command.get_program(), "code");
+        assert_eq!(command.get_args().collect::<Vec<_>>(), ["--wait", "+4", "2025-09-01.md"]);
+        assert!(editor_command(" ", Path::new("2025-09-01.md"), None).is_none());
+    }
+
+    #[test]
+    fn test_draw() {
+        let (args, config) = (args(), Config::default());
+        let mut app = App::new(&args, &config, DurationFormat::default(), snapshot());
+        app.selected = date(1);
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+
+        let screen: String = terminal.backend().buffer().content().iter().map(ratatui::buffer::Cell::symbol).collect();
+        assert!(screen.contains(" September 2025 "));
+        assert!(screen.contains("  1!  2   3!  4 "));
+        assert!(screen.contains("Week of 2025-09-01 - 2025-09-07"));
+        assert!(screen.contains("13h 00m"));
+        assert!(screen.contains(" Mon 2025-09-01: 5h 00m "));
+        assert!(screen.contains("   3  stop at 13:00 has no matching start"));
+    }
+}